@@ -17,20 +17,19 @@ async fn main() {
     let token = env::var("ACCESS_TOKEN").expect("ACCESS_TOKEN env var required");
     let fio = Fio::new(&token);
 
-    let transaction = DomesticTransaction {
-        account_from: env::var("ACCOUNT_FROM").unwrap_or_else(|_| "0000000000".to_string()),
-        currency: "CZK".to_string(),
-        amount: Decimal::new(100, 2),
-        account_to: env::var("ACCOUNT_TO").unwrap_or_else(|_| "0000000000".to_string()),
-        bank_code: "2010".to_string(),
-        ks: None,
-        vs: Some("1234567890".to_string()),
-        ss: None,
-        date: "2025-12-31".to_string(),
-        message_for_recipient: Some("Test payment from fiocz-rs".to_string()),
-        comment: None,
-        payment_reason: None,
-        payment_type: None,
+    let transaction = match DomesticTransaction::builder()
+        .account_from(&env::var("ACCOUNT_FROM").unwrap_or_else(|_| "2101179627".to_string()))
+        .amount(Decimal::new(100, 2))
+        .account_to(&env::var("ACCOUNT_TO").unwrap_or_else(|_| "2000145399/0800".to_string()))
+        .vs("1234567890")
+        .message_for_recipient("Test payment from fiocz-rs")
+        .build()
+    {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            eprintln!("Invalid payment order: {e}");
+            return;
+        }
     };
 
     let import = Import::builder().domestic(transaction).build();
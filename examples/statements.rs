@@ -4,6 +4,7 @@
 
 use std::env;
 
+use fiocz_rs::types::account_statement::LastStatementId;
 use fiocz_rs::types::ExportFormat;
 use fiocz_rs::Fio;
 
@@ -19,8 +20,11 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let year = &args[1];
-    let id = &args[2];
+    let (Ok(year), Ok(id)) = (args[1].parse(), args[2].parse()) else {
+        eprintln!("Year and statement ID must be numbers");
+        std::process::exit(1);
+    };
+    let statement = LastStatementId::new(year, id);
     let fio = Fio::new(&token);
 
     if let Some(fmt) = args.get(3) {
@@ -37,16 +41,16 @@ async fn main() {
                 std::process::exit(1);
             }
         };
-        match fio.statements_raw(year, id, format).await {
+        match fio.statements_raw(statement, format).await {
             Ok(body) => println!("{body}"),
             Err(e) => eprintln!("Error: {e:?}"),
         }
     } else {
-        match fio.statements(year, id).await {
+        match fio.statements(statement).await {
             Ok(stmt) => {
                 let info = &stmt.account_statement.info;
                 let txns = &stmt.account_statement.transaction_list.transaction;
-                println!("Statement {}/{}", year, id);
+                println!("Statement {}/{}", statement.id, statement.year);
                 println!("Account: {} ({})", info.account_id, info.currency);
                 println!("Transactions: {}", txns.len());
             }
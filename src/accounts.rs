@@ -0,0 +1,321 @@
+//! Registry of several accounts, each with its own token
+//!
+//! Every Fio account (e.g. CZK, EUR and USD) has its own API token and rate
+//! limit. [`FioAccounts`] keeps one [`Fio`] client per token under a
+//! user-chosen key such as an alias or the account IBAN, and queries all of
+//! them concurrently. Keys registered with the same token share one client
+//! state and one request per query.
+//!
+//! ```no_run
+//! use fiocz_rs::accounts::FioAccounts;
+//!
+//! # async fn run() {
+//! let mut accounts = FioAccounts::new();
+//! accounts.insert("czk", "token-czk").insert("eur", "token-eur");
+//! for (alias, result) in accounts.movements_in_period("2025-01-01", "2025-01-31").await {
+//!     match result {
+//!         Ok(account) => println!("{alias}: {} movements", account.movements.len()),
+//!         Err(e) => eprintln!("{alias}: {e}"),
+//!     }
+//! }
+//! # }
+//! ```
+
+use futures_util::future::join_all;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::types::account_statement::{Info, Statement};
+use crate::types::movement::Movement;
+use crate::Fio;
+
+/// Movements of one account, tagged with its account info
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct AccountMovements {
+    /// Account info returned with the movements
+    pub info: Info,
+    /// Parsed movements
+    pub movements: Vec<Movement>,
+}
+
+impl AccountMovements {
+    fn from_statement(statement: Statement) -> Result<Self, Error> {
+        let movements = statement.movements()?;
+        Ok(Self {
+            info: statement.account_statement.info,
+            movements,
+        })
+    }
+}
+
+/// Registry of API clients keyed by alias or IBAN
+#[derive(Clone, Default)]
+pub struct FioAccounts {
+    accounts: BTreeMap<String, Fio>,
+}
+
+impl FioAccounts {
+    /// Create new empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an account
+    ///
+    /// Registering the same token under several keys shares one client, and
+    /// therefore one rate limiter, between them.
+    /// # Arguments
+    /// * `key` - Alias or IBAN identifying the account
+    /// * `token` - Fio API token of the account
+    pub fn insert(&mut self, key: &str, token: &str) -> &mut Self {
+        let fio = self
+            .accounts
+            .values()
+            .find(|fio| fio.token == token)
+            .cloned()
+            .unwrap_or_else(|| Fio::new(token));
+        self.accounts.insert(key.to_string(), fio);
+        self
+    }
+
+    /// Register an account with a configured client
+    ///
+    /// Use this to set dry-run mode or token permissions per account. When
+    /// another key already uses the same token, the client takes over its
+    /// rate limiter and known permissions.
+    /// # Arguments
+    /// * `key` - Alias or IBAN identifying the account
+    /// * `fio` - Client of the account
+    pub fn insert_client(&mut self, key: &str, mut fio: Fio) -> &mut Self {
+        if let Some(existing) = self.accounts.values().find(|f| f.token == fio.token) {
+            fio.last_request = Arc::clone(&existing.last_request);
+            fio.permissions = Arc::clone(&existing.permissions);
+        }
+        self.accounts.insert(key.to_string(), fio);
+        self
+    }
+
+    /// Remove an account, returning its client
+    pub fn remove(&mut self, key: &str) -> Option<Fio> {
+        self.accounts.remove(key)
+    }
+
+    /// Client registered under a key
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&Fio> {
+        self.accounts.get(key)
+    }
+
+    /// Registered keys in sorted order
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    /// Number of registered accounts
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Whether no account is registered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Get movements in period for all accounts concurrently
+    ///
+    /// Keys sharing a token share one request; when it fails, each of them
+    /// gets the error wrapped in [`Error::Shared`]. Use [`Error::root`] to
+    /// tell e.g. a rate limit from other failures.
+    /// # Arguments
+    /// * `start` - Start date in format YYYY-MM-DD
+    /// * `end` - End date in format YYYY-MM-DD
+    /// # Returns
+    /// * Result per account key, in key order
+    pub async fn movements_in_period(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Vec<(String, Result<AccountMovements, Error>)> {
+        self.fan_out(|fio| async move {
+            fio.movements_in_period(start, end)
+                .await
+                .and_then(AccountMovements::from_statement)
+        })
+        .await
+    }
+
+    /// Get movements since last download for all accounts concurrently
+    ///
+    /// The download bookmark belongs to the token, so keys sharing a token
+    /// share one download; when it fails, each of them gets the error
+    /// wrapped in [`Error::Shared`].
+    /// # Returns
+    /// * Result per account key, in key order
+    pub async fn movements_since_last(&self) -> Vec<(String, Result<AccountMovements, Error>)> {
+        self.fan_out(|fio| async move {
+            fio.movements_since_last()
+                .await
+                .and_then(AccountMovements::from_statement)
+        })
+        .await
+    }
+
+    /// Run `query` once per distinct token and hand the result to every key
+    /// registered with that token
+    ///
+    /// When such a query fails, each of those keys gets the error wrapped in
+    /// [`Error::Shared`].
+    async fn fan_out<'a, F, Fut>(
+        &'a self,
+        query: F,
+    ) -> Vec<(String, Result<AccountMovements, Error>)>
+    where
+        F: Fn(&'a Fio) -> Fut,
+        Fut: std::future::Future<Output = Result<AccountMovements, Error>>,
+    {
+        let mut groups: Vec<(&Fio, Vec<&String>)> = vec![];
+        for (key, fio) in &self.accounts {
+            match groups.iter_mut().find(|(f, _)| f.token == fio.token) {
+                Some((_, keys)) => keys.push(key),
+                None => groups.push((fio, vec![key])),
+            }
+        }
+        let results = join_all(groups.iter().map(|(fio, _)| query(fio))).await;
+
+        let mut tagged = vec![];
+        for ((_, keys), result) in groups.into_iter().zip(results) {
+            if let [key] = keys[..] {
+                tagged.push((key.clone(), result));
+                continue;
+            }
+            let result = result.map_err(Arc::new);
+            for key in keys {
+                let result = match &result {
+                    Ok(movements) => Ok(movements.clone()),
+                    Err(e) => Err(Error::Shared(Arc::clone(e))),
+                };
+                tagged.push((key.clone(), result));
+            }
+        }
+        tagged.sort_by(|(a, _), (b, _)| a.cmp(b));
+        tagged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::StatementJson;
+    use crate::mock::MockServer;
+
+    #[test]
+    fn same_token_shares_rate_limiter() {
+        let mut accounts = FioAccounts::new();
+        accounts
+            .insert("czk", "token-a")
+            .insert("CZ6508000000192000145399", "token-a")
+            .insert("eur", "token-b");
+        let czk = accounts.get("czk").unwrap();
+        let iban = accounts.get("CZ6508000000192000145399").unwrap();
+        let eur = accounts.get("eur").unwrap();
+        assert!(Arc::ptr_eq(&czk.last_request, &iban.last_request));
+        assert!(!Arc::ptr_eq(&czk.last_request, &eur.last_request));
+    }
+
+    #[test]
+    fn keys_sorted_and_removable() {
+        let mut accounts = FioAccounts::new();
+        accounts
+            .insert("usd", "c")
+            .insert("czk", "a")
+            .insert("eur", "b");
+        assert_eq!(
+            accounts.keys().collect::<Vec<_>>(),
+            vec!["czk", "eur", "usd"]
+        );
+        assert!(accounts.remove("eur").is_some());
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts.get("eur").is_none());
+    }
+
+    #[test]
+    fn configured_client_shares_limiter() {
+        let mut accounts = FioAccounts::new();
+        accounts
+            .insert("czk", "token-a")
+            .insert_client("staging", Fio::new("token-a").with_dry_run(true));
+        let czk = accounts.get("czk").unwrap();
+        let staging = accounts.get("staging").unwrap();
+        assert!(staging.is_dry_run());
+        assert!(!czk.is_dry_run());
+        assert!(Arc::ptr_eq(&czk.last_request, &staging.last_request));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn shared_token_pulled_once() {
+        let server = MockServer::start(|_, path| {
+            let account = if path.contains("token-a") {
+                "2101179627"
+            } else {
+                "2000145399"
+            };
+            let json = StatementJson {
+                account,
+                ..StatementJson::default()
+            }
+            .json();
+            if path.contains("token-c") {
+                (409, vec![])
+            } else {
+                (200, json.into_bytes())
+            }
+        })
+        .await;
+        let mut accounts = FioAccounts::new();
+        accounts
+            .insert_client("czk", server.fio("token-a"))
+            .insert_client("CZ5020100000002101179627", server.fio("token-a"))
+            .insert_client("eur", server.fio("token-b"))
+            .insert_client("usd", server.fio("token-c"))
+            .insert_client("usd-alias", server.fio("token-c"));
+
+        let results = accounts.movements_since_last().await;
+        let mut requests = server.requests();
+        requests.sort();
+        assert_eq!(
+            requests,
+            [
+                "GET /last/token-a/transactions.json",
+                "GET /last/token-b/transactions.json",
+                "GET /last/token-c/transactions.json"
+            ]
+        );
+        let keys: Vec<_> = results.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(
+            keys,
+            ["CZ5020100000002101179627", "czk", "eur", "usd", "usd-alias"]
+        );
+        assert_eq!(
+            results[0].1.as_ref().unwrap().info.account_id,
+            results[1].1.as_ref().unwrap().info.account_id
+        );
+        assert!(results[3..].iter().all(
+            |(_, r)| matches!(r, Err(e @ Error::Shared(_)) if matches!(e.root(), Error::Limit))
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_dates_reported_per_account() {
+        let mut accounts = FioAccounts::new();
+        accounts.insert("czk", "a").insert("eur", "b");
+        let results = accounts.movements_in_period("2025-1-1", "2025-01-31").await;
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|(_, r)| matches!(r, Err(Error::InvalidDateFormat))));
+    }
+}
@@ -0,0 +1,463 @@
+//! Four-eyes approval of payment imports
+//!
+//! [`ApprovalQueue`] keeps proposed imports as JSON files in a directory,
+//! together with the hash of the orders (see [`crate::ledger::fingerprint`]).
+//! Approvers sign off on that hash; an import can only be submitted once it
+//! has the required number of approvals from people other than its author,
+//! all given to the orders exactly as they are on disk now. Imports are
+//! submitted through a [`LedgerImporter`], and moved to the `submitted`
+//! subdirectory once the bank accepted them.
+//!
+//! The hashes guard against accidental edits, not against tampering: the
+//! proposal hash and the approvals are stored in the same JSON file as the
+//! orders, so anyone who can write to the queue directory can change the
+//! orders and recompute the hashes. Protect the directory accordingly.
+//!
+//! ```no_run
+//! use fiocz_rs::approval::ApprovalQueue;
+//! use fiocz_rs::ledger::{ImportLedger, LedgerImporter, Resubmission};
+//! use fiocz_rs::types::transaction::Import;
+//! use fiocz_rs::Fio;
+//!
+//! # async fn run(import: Import) -> Result<(), fiocz_rs::error::Error> {
+//! let queue = ApprovalQueue::new("approvals", 2);
+//! let id = queue.propose(import, "alice")?;
+//! queue.approve(&id, "bob")?;
+//! queue.approve(&id, "carol")?;
+//! let importer = LedgerImporter::new(
+//!     Fio::new("your-api-token"),
+//!     ImportLedger::new("imports.jsonl"),
+//!     Resubmission::Refuse,
+//! );
+//! let response = queue.submit(&importer, &id).await?;
+//! println!("Accepted: {}", response.is_ok());
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Local;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::ledger::{fingerprint, LedgerImporter};
+use crate::types::import_response::ImportResponse;
+use crate::types::transaction::Import;
+
+/// Sign-off of one approver
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Approval {
+    /// Identity of the approver
+    pub approver: String,
+    /// Time of the approval (RFC 3339)
+    pub approved_at: String,
+    /// Hash of the orders that were approved
+    pub hash: String,
+}
+
+/// Import waiting for approval
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingImport {
+    /// Queue ID
+    pub id: String,
+    /// Hash of the orders when proposed
+    pub hash: String,
+    /// Identity of the author
+    pub proposed_by: String,
+    /// Time of the proposal (RFC 3339)
+    pub proposed_at: String,
+    /// Approvals so far
+    pub approvals: Vec<Approval>,
+    /// Orders to import
+    pub import: Import,
+}
+
+impl PendingImport {
+    /// Whether the orders still have the hash they were proposed with
+    ///
+    /// The reference hash is read from the same file as the orders, so this
+    /// detects edits that did not also update [`Self::hash`].
+    #[must_use]
+    pub fn is_unmodified(&self) -> bool {
+        fingerprint(&self.import) == self.hash
+    }
+
+    /// Approvals given to the current orders
+    ///
+    /// Like [`Self::is_unmodified`], this trusts the approvals stored in the
+    /// file.
+    pub fn valid_approvals(&self) -> impl Iterator<Item = &Approval> {
+        let hash = fingerprint(&self.import);
+        self.approvals
+            .iter()
+            .filter(move |a| a.hash == hash && a.approver != self.proposed_by)
+    }
+}
+
+/// Directory of imports waiting for approval
+#[derive(Debug, Clone)]
+pub struct ApprovalQueue {
+    dir: PathBuf,
+    required: usize,
+}
+
+impl ApprovalQueue {
+    /// Create new queue
+    /// # Arguments
+    /// * `dir` - Directory of the pending files, created on first proposal
+    /// * `required` - Number of approvals needed before submission
+    /// # Panics
+    /// * `required` is zero, which would let imports through unapproved
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>, required: usize) -> Self {
+        assert!(
+            required >= 1,
+            "an approval queue needs at least one approval"
+        );
+        Self {
+            dir: dir.into(),
+            required,
+        }
+    }
+
+    /// Add an import to the queue
+    /// # Arguments
+    /// * `import` - Orders to approve
+    /// * `author` - Identity of the person proposing the import
+    /// # Returns
+    /// * `String` - Queue ID, derived from the hash of the orders
+    /// # Errors
+    /// * `Error::Approval` - The same orders are already waiting
+    /// * `Error::Io` / `Error::SerdeJsonError` - The pending file could not be written
+    pub fn propose(&self, import: Import, author: &str) -> Result<String, Error> {
+        // Store what will be read back, so the hash survives the JSON round trip
+        let import: Import = serde_json::from_str(&serde_json::to_string(&import)?)?;
+        let hash = fingerprint(&import);
+        let id = hash[..16].to_string();
+        if self.path(&id).exists() {
+            return Err(Error::Approval(format!(
+                "{id} is already waiting for approval"
+            )));
+        }
+        let pending = PendingImport {
+            id: id.clone(),
+            hash,
+            proposed_by: author.to_string(),
+            proposed_at: Local::now().to_rfc3339(),
+            approvals: vec![],
+            import,
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        write(&self.path(&id), &pending)?;
+        info!(
+            "{author} proposed import {id} with {} orders",
+            pending.import.orders.len()
+        );
+        Ok(id)
+    }
+
+    /// Imports waiting for approval, oldest first
+    /// # Errors
+    /// * `Error::Io` / `Error::SerdeJsonError` - A pending file could not be read
+    pub fn pending(&self) -> Result<Vec<PendingImport>, Error> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut pending = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                pending.push(read(&path)?);
+            }
+        }
+        pending.sort_by(|a: &PendingImport, b| a.proposed_at.cmp(&b.proposed_at));
+        Ok(pending)
+    }
+
+    /// Load a pending import
+    /// # Errors
+    /// * `Error::Approval` - No such import is waiting
+    /// * `Error::Io` / `Error::SerdeJsonError` - The pending file could not be read
+    pub fn get(&self, id: &str) -> Result<PendingImport, Error> {
+        let path = self.path(id);
+        let well_formed = id.len() == 16 && id.bytes().all(|b| b.is_ascii_hexdigit());
+        if !well_formed || !path.exists() {
+            return Err(Error::Approval(format!("{id} is not waiting for approval")));
+        }
+        read(&path)
+    }
+
+    /// Approve an import
+    ///
+    /// The pending file is read, changed and written back without a lock, so
+    /// two approvals given at the same moment can overwrite each other; run
+    /// approvals of one queue from one process at a time.
+    /// # Arguments
+    /// * `id` - Queue ID
+    /// * `approver` - Identity of the approver
+    /// # Returns
+    /// * `PendingImport` - The import with the new approval
+    /// # Errors
+    /// * `Error::Approval` - The import was modified, or the approver is its
+    ///   author or already approved it
+    /// * `Error::Io` / `Error::SerdeJsonError` - The pending file could not be
+    ///   read or written
+    pub fn approve(&self, id: &str, approver: &str) -> Result<PendingImport, Error> {
+        let mut pending = self.get(id)?;
+        if !pending.is_unmodified() {
+            return Err(Error::Approval(format!(
+                "{id} was modified after it was proposed"
+            )));
+        }
+        if pending.proposed_by == approver {
+            return Err(Error::Approval(format!(
+                "{approver} cannot approve their own import {id}"
+            )));
+        }
+        if pending.approvals.iter().any(|a| a.approver == approver) {
+            return Err(Error::Approval(format!("{approver} already approved {id}")));
+        }
+        pending.approvals.push(Approval {
+            approver: approver.to_string(),
+            approved_at: Local::now().to_rfc3339(),
+            hash: pending.hash.clone(),
+        });
+        write(&self.path(id), &pending)?;
+        info!("{approver} approved import {id}");
+        Ok(pending)
+    }
+
+    /// Remove an import from the queue without submitting it
+    /// # Errors
+    /// * `Error::Approval` - No such import is waiting
+    /// * `Error::Io` - The pending file could not be removed
+    pub fn withdraw(&self, id: &str) -> Result<(), Error> {
+        self.get(id)?;
+        std::fs::remove_file(self.path(id))?;
+        Ok(())
+    }
+
+    /// Submit a fully approved, unmodified import
+    ///
+    /// The pending file is moved to the `submitted` subdirectory only when
+    /// the bank accepted the import. It stays in the queue when the bank
+    /// rejected it, when the request failed (the ledger then refuses to send
+    /// it again until the outcome is resolved) and in dry-run mode.
+    /// # Arguments
+    /// * `importer` - Importer recording the submission in its ledger
+    /// * `id` - Queue ID
+    /// # Returns
+    /// * `ImportResponse` - Parsed bank response, also when the bank rejected
+    ///   the import
+    /// # Errors
+    /// * `Error::Approval` - The import was modified or lacks approvals
+    /// * `Error::DuplicateImport` - The ledger records the import as
+    ///   submitted or sent with unknown outcome
+    /// * `Error::InsufficientRights` / `Error::Limit` / ... - The import failed
+    pub async fn submit(
+        &self,
+        importer: &LedgerImporter,
+        id: &str,
+    ) -> Result<ImportResponse, Error> {
+        let pending = self.check_approved(id)?;
+        let response = importer.import(pending.import).await?;
+        if importer.is_dry_run() {
+            info!("Dry run, import {id} stays in the queue");
+        } else if !response.is_ok() {
+            warn!(
+                "Import {id} rejected with error code {}, it stays in the queue",
+                response.error_code
+            );
+        } else {
+            let submitted = self.dir.join("submitted");
+            std::fs::create_dir_all(&submitted)?;
+            std::fs::rename(self.path(id), submitted.join(format!("{id}.json")))?;
+            info!("Submitted import {id}");
+        }
+        Ok(response)
+    }
+
+    /// Load an import and make sure it may be submitted
+    fn check_approved(&self, id: &str) -> Result<PendingImport, Error> {
+        let pending = self.get(id)?;
+        if !pending.is_unmodified() {
+            return Err(Error::Approval(format!(
+                "{id} was modified after it was proposed"
+            )));
+        }
+        let approvals = pending.valid_approvals().count();
+        if approvals < self.required {
+            return Err(Error::Approval(format!(
+                "{id} has {approvals} of {} approvals",
+                self.required
+            )));
+        }
+        Ok(pending)
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+}
+
+fn read(path: &Path) -> Result<PendingImport, Error> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// Write to a temporary sibling and rename into place
+fn write(path: &Path, pending: &PendingImport) -> Result<(), Error> {
+    let mut tmp = path.to_path_buf().into_os_string();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(pending)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::domestic_import;
+    use crate::ledger::{ImportLedger, Resubmission};
+    use crate::mock::MockServer;
+    use crate::Fio;
+
+    fn queue(name: &str) -> ApprovalQueue {
+        let dir =
+            std::env::temp_dir().join(format!("fiocz-approval-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        ApprovalQueue::new(dir, 2)
+    }
+
+    #[test]
+    fn approvals_required() {
+        let queue = queue("required");
+        let id = queue.propose(domestic_import(100), "alice").unwrap();
+        assert!(queue.get(&id).unwrap().is_unmodified());
+        assert!(matches!(queue.get("../secrets"), Err(Error::Approval(_))));
+        assert!(matches!(
+            queue.propose(domestic_import(100), "bob"),
+            Err(Error::Approval(_))
+        ));
+
+        assert!(matches!(
+            queue.approve(&id, "alice"),
+            Err(Error::Approval(_))
+        ));
+        queue.approve(&id, "bob").unwrap();
+        assert!(matches!(queue.approve(&id, "bob"), Err(Error::Approval(_))));
+        assert!(matches!(queue.check_approved(&id), Err(Error::Approval(_))));
+        let pending = queue.approve(&id, "carol").unwrap();
+        assert_eq!(pending.approvals[1].approver, "carol");
+        assert!(queue.check_approved(&id).is_ok());
+        assert_eq!(queue.pending().unwrap().len(), 1);
+
+        queue.withdraw(&id).unwrap();
+        assert!(queue.pending().unwrap().is_empty());
+        std::fs::remove_dir_all(&queue.dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one approval")]
+    fn zero_approvals_refused() {
+        let _ = ApprovalQueue::new("approvals", 0);
+    }
+
+    #[test]
+    fn modified_import_rejected() {
+        let queue = queue("modified");
+        let id = queue.propose(domestic_import(100), "alice").unwrap();
+        queue.approve(&id, "bob").unwrap();
+        queue.approve(&id, "carol").unwrap();
+
+        let path = queue.path(&id);
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("19-2000145399", "2000145399");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(!queue.get(&id).unwrap().is_unmodified());
+        assert_eq!(queue.get(&id).unwrap().valid_approvals().count(), 0);
+        assert!(matches!(queue.check_approved(&id), Err(Error::Approval(_))));
+        assert!(matches!(
+            queue.approve(&id, "dave"),
+            Err(Error::Approval(_))
+        ));
+        std::fs::remove_dir_all(&queue.dir).unwrap();
+    }
+
+    fn importer(fio: Fio, queue: &ApprovalQueue) -> LedgerImporter {
+        LedgerImporter::new(
+            fio,
+            ImportLedger::new(queue.dir.join("ledger.jsonl")),
+            Resubmission::Refuse,
+        )
+    }
+
+    async fn server(error_code: u32) -> MockServer {
+        MockServer::start(move |_, _| {
+            let xml = format!(
+                "<responseImport><result><errorCode>{error_code}</errorCode>\
+                 <idInstruction>7</idInstruction></result></responseImport>"
+            );
+            (200, xml.into_bytes())
+        })
+        .await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn submit_moves_file_once_accepted() {
+        let queue = queue("submit");
+        let id = queue.propose(domestic_import(100), "alice").unwrap();
+        let dry_run = importer(Fio::new("token").with_dry_run(true), &queue);
+        assert!(matches!(
+            queue.submit(&dry_run, &id).await,
+            Err(Error::Approval(_))
+        ));
+        queue.approve(&id, "bob").unwrap();
+        queue.approve(&id, "carol").unwrap();
+        let submitted = queue.dir.join("submitted").join(format!("{id}.json"));
+
+        // Dry run and a rejection keep the import in the queue
+        assert!(queue.submit(&dry_run, &id).await.unwrap().is_ok());
+        assert_eq!(queue.pending().unwrap().len(), 1);
+        let rejecting = server(11).await;
+        let response = queue
+            .submit(&importer(rejecting.fio("token"), &queue), &id)
+            .await
+            .unwrap();
+        assert!(!response.is_ok());
+        assert_eq!(queue.pending().unwrap().len(), 1);
+
+        let accepting = server(0).await;
+        let importer = importer(accepting.fio("token"), &queue);
+        assert!(queue.submit(&importer, &id).await.unwrap().is_ok());
+        assert!(queue.pending().unwrap().is_empty());
+        assert!(submitted.exists());
+        std::fs::remove_dir_all(&queue.dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn network_failure_not_archived() {
+        let queue = queue("network");
+        let id = queue.propose(domestic_import(100), "alice").unwrap();
+        queue.approve(&id, "bob").unwrap();
+        queue.approve(&id, "carol").unwrap();
+        let unreachable = importer(
+            Fio::new("token").with_base_url("http://127.0.0.1:1"),
+            &queue,
+        );
+        assert!(matches!(
+            queue.submit(&unreachable, &id).await,
+            Err(Error::Reqwest(_))
+        ));
+        assert_eq!(queue.pending().unwrap().len(), 1);
+        // The outcome is unknown, so it is not sent again
+        assert!(matches!(
+            queue.submit(&unreachable, &id).await,
+            Err(Error::DuplicateImport(_))
+        ));
+        std::fs::remove_dir_all(&queue.dir).unwrap();
+    }
+}
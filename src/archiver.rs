@@ -0,0 +1,346 @@
+//! Archive of official account statements
+//!
+//! [`StatementArchiver`] walks all statements from a configured year up to
+//! [`Fio::last_statement_id`] and stores each one in the requested
+//! [`ExportFormat`]s as `<root>/<year>/<year>-<id>.<format>`, e.g.
+//! `archive/2024/2024-003.pdf`. Statements already present on disk are
+//! skipped, so an interrupted run simply resumes.
+//!
+//! The API does not say how many statements a past year has, so for years
+//! before the last statement the archiver keeps requesting the next ID until
+//! the bank refuses one beyond those already archived. The number of
+//! statements found is then recorded in `<root>/<year>/.complete`, and later
+//! runs do not ask about that year again.
+
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::types::account_statement::LastStatementId;
+use crate::types::ExportFormat;
+use crate::Fio;
+
+/// Upper bound of statements in one year (daily statements)
+const MAX_STATEMENTS_PER_YEAR: u32 = 366;
+
+/// File in a year directory holding the number of statements of a closed year
+const COMPLETE_MARKER: &str = ".complete";
+
+/// Statement stored by the archiver
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ArchivedStatement {
+    /// Statement year and ID
+    pub statement: LastStatementId,
+    /// Format of the stored file
+    pub format: ExportFormat,
+    /// Path of the stored file
+    pub path: PathBuf,
+}
+
+/// Statement that could not be downloaded
+#[derive(Debug)]
+pub struct ArchiveFailure {
+    /// Statement year and ID
+    pub statement: LastStatementId,
+    /// Requested format
+    pub format: ExportFormat,
+    /// Error returned by the API
+    pub error: Error,
+}
+
+/// Result of [`StatementArchiver::archive`]
+#[derive(Debug, Default)]
+pub struct ArchiveReport {
+    /// Files written during this run
+    pub downloaded: Vec<ArchivedStatement>,
+    /// Number of files already present and skipped
+    pub skipped: usize,
+    /// Downloads that failed
+    pub failed: Vec<ArchiveFailure>,
+    /// Statements still missing at least one format after the run
+    pub gaps: Vec<LastStatementId>,
+}
+
+/// Downloads official statements into a directory tree
+pub struct StatementArchiver {
+    fio: Fio,
+    root: PathBuf,
+    from_year: u16,
+    formats: Vec<ExportFormat>,
+}
+
+impl StatementArchiver {
+    /// Create new archiver storing JSON, PDF and MT940 files
+    /// # Arguments
+    /// * `fio` - API client
+    /// * `root` - Root directory of the archive
+    /// * `from_year` - First year to archive
+    #[must_use]
+    pub fn new(fio: Fio, root: impl Into<PathBuf>, from_year: u16) -> Self {
+        Self {
+            fio,
+            root: root.into(),
+            from_year,
+            formats: vec![ExportFormat::Json, ExportFormat::Pdf, ExportFormat::Mt940],
+        }
+    }
+
+    /// Set the formats to archive
+    #[must_use]
+    pub fn formats(mut self, formats: &[ExportFormat]) -> Self {
+        self.formats = formats.to_vec();
+        self
+    }
+
+    /// Path of an archived statement file
+    #[must_use]
+    pub fn path_for(&self, statement: LastStatementId, format: ExportFormat) -> PathBuf {
+        let LastStatementId { year, id } = statement;
+        self.root
+            .join(year.to_string())
+            .join(format!("{year}-{id:03}.{format}"))
+    }
+
+    /// Download all statements missing from the archive
+    ///
+    /// Failures of individual statements are collected in the report. Rate
+    /// limit, network and token errors abort the run; everything stored so
+    /// far stays on disk.
+    /// # Returns
+    /// * `ArchiveReport` - Downloaded, skipped and failed statements
+    /// # Errors
+    /// * `Error::Limit` - Too many requests
+    /// * `Error::Reqwest` - Network failure
+    /// * `Error::InvalidToken` - Invalid token
+    /// * `Error::Io` - A file could not be written
+    pub async fn archive(&self) -> Result<ArchiveReport, Error> {
+        let last = self.fio.last_statement_id().await?;
+        let mut report = ArchiveReport::default();
+
+        for year in self.from_year..=last.year {
+            let (known, end) = if year == last.year {
+                (last.id, Some(last.id))
+            } else {
+                match self.completed_year(year)? {
+                    Some(count) => (count, Some(count)),
+                    None => (self.highest_archived(year)?, None),
+                }
+            };
+            for id in 1..=end.unwrap_or(MAX_STATEMENTS_PER_YEAR) {
+                let statement = LastStatementId::new(year, id);
+                if !self.archive_one(statement, known, &mut report).await? {
+                    // Past the last archived statement of a closed year, the
+                    // first statement the bank does not have ends the year.
+                    info!("Year {year} has {} statements", id - 1);
+                    self.mark_complete(year, id - 1).await?;
+                    break;
+                }
+            }
+        }
+        info!(
+            "Archived {} files, skipped {}, {} failures",
+            report.downloaded.len(),
+            report.skipped,
+            report.failed.len()
+        );
+        Ok(report)
+    }
+
+    /// Archive all formats of one statement
+    ///
+    /// Returns `false` when the statement lies beyond `known` and the bank
+    /// answers the first format with 404, meaning it does not exist.
+    async fn archive_one(
+        &self,
+        statement: LastStatementId,
+        known: u32,
+        report: &mut ArchiveReport,
+    ) -> Result<bool, Error> {
+        let mut found = false;
+        let mut failures = vec![];
+        for &format in &self.formats {
+            let path = self.path_for(statement, format);
+            if tokio::fs::try_exists(&path).await? {
+                report.skipped += 1;
+                found = true;
+                continue;
+            }
+            match self.fio.statements_bytes(statement, format).await {
+                Ok(body) => {
+                    write_atomic(&path, &body).await?;
+                    report.downloaded.push(ArchivedStatement {
+                        statement,
+                        format,
+                        path,
+                    });
+                    found = true;
+                }
+                Err(e @ (Error::Limit | Error::Reqwest(_))) => return Err(e),
+                // Beyond the known statements, a 404 for the first format
+                // means there is no such statement; the token just worked for
+                // the last statement ID. Any other error is a failure.
+                Err(Error::InvalidToken) if statement.id > known && !found => return Ok(false),
+                Err(e @ Error::InvalidToken) if statement.id <= known => return Err(e),
+                Err(error) => failures.push(ArchiveFailure {
+                    statement,
+                    format,
+                    error,
+                }),
+            }
+        }
+        if !failures.is_empty() {
+            for failure in &failures {
+                warn!(
+                    "Statement {statement} ({}) failed: {}",
+                    failure.format, failure.error
+                );
+            }
+            report.failed.append(&mut failures);
+            report.gaps.push(statement);
+        }
+        Ok(true)
+    }
+
+    /// Number of statements of a year recorded as complete
+    fn completed_year(&self, year: u16) -> Result<Option<u32>, Error> {
+        let path = self.root.join(year.to_string()).join(COMPLETE_MARKER);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(content.trim().parse().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Record that a closed year has `count` statements
+    async fn mark_complete(&self, year: u16, count: u32) -> Result<(), Error> {
+        let path = self.root.join(year.to_string()).join(COMPLETE_MARKER);
+        write_atomic(&path, count.to_string().as_bytes()).await
+    }
+
+    /// Highest statement ID with at least one archived file in a year
+    fn highest_archived(&self, year: u16) -> Result<u32, Error> {
+        let dir = self.root.join(year.to_string());
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let prefix = format!("{year}-");
+        let mut highest = 0;
+        for entry in entries {
+            let name = entry?.file_name();
+            let id = name
+                .to_str()
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.split_once('.'))
+                .and_then(|(id, _)| id.parse::<u32>().ok());
+            if let Some(id) = id {
+                highest = highest.max(id);
+            }
+        }
+        Ok(highest)
+    }
+}
+
+async fn write_atomic(path: &Path, body: &[u8]) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    tokio::fs::write(&tmp, body).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockServer;
+
+    fn archiver(root: &Path) -> StatementArchiver {
+        StatementArchiver::new(Fio::new("token"), root, 2023)
+    }
+
+    #[test]
+    fn path_layout() {
+        let a = archiver(Path::new("archive"));
+        assert_eq!(
+            a.path_for(LastStatementId::new(2024, 3), ExportFormat::Pdf),
+            Path::new("archive/2024/2024-003.pdf")
+        );
+        assert_eq!(
+            a.path_for(LastStatementId::new(2024, 12), ExportFormat::Mt940),
+            Path::new("archive/2024/2024-012.sta")
+        );
+    }
+
+    #[test]
+    fn highest_archived_scans_directory() {
+        let root = std::env::temp_dir().join(format!("fiocz-archive-{}", std::process::id()));
+        let a = archiver(&root);
+        assert_eq!(a.highest_archived(2023).unwrap(), 0);
+        std::fs::create_dir_all(root.join("2023")).unwrap();
+        for name in ["2023-001.json", "2023-011.pdf", "2023-002.sta", "notes.txt"] {
+            std::fs::write(root.join("2023").join(name), b"x").unwrap();
+        }
+        assert_eq!(a.highest_archived(2023).unwrap(), 11);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn closed_year_probed_once() {
+        // 2023 has two statements, the PDF of the second one keeps failing
+        let server = MockServer::start(|_, path| match path {
+            "/lastStatement/token/statement" => (200, b"2024,1".to_vec()),
+            "/by-id/token/2023/3/transactions.json" => (404, vec![]),
+            "/by-id/token/2023/2/transactions.pdf" => (500, vec![]),
+            _ => (200, b"statement".to_vec()),
+        })
+        .await;
+        let root = std::env::temp_dir().join(format!("fiocz-archive-run-{}", std::process::id()));
+        let a = StatementArchiver::new(server.fio("token"), &root, 2023)
+            .formats(&[ExportFormat::Json, ExportFormat::Pdf]);
+
+        let report = a.archive().await.unwrap();
+        assert_eq!(report.downloaded.len(), 5);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.gaps, [LastStatementId::new(2023, 2)]);
+        assert_eq!(a.completed_year(2023).unwrap(), Some(2));
+
+        // The second run retries the gap without probing for statement 3
+        let before = server.requests().len();
+        let report = a.archive().await.unwrap();
+        assert_eq!(report.skipped, 5);
+        assert_eq!(report.gaps, [LastStatementId::new(2023, 2)]);
+        assert_eq!(
+            server.requests()[before..],
+            [
+                "GET /lastStatement/token/statement",
+                "GET /by-id/token/2023/2/transactions.pdf"
+            ]
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn probe_failure_is_not_end_of_year() {
+        // Statement 3 of 2023 fails with a server error, statement 4 is absent
+        let server = MockServer::start(|_, path| match path {
+            "/lastStatement/token/statement" => (200, b"2024,0".to_vec()),
+            "/by-id/token/2023/3/transactions.json" => (500, vec![]),
+            "/by-id/token/2023/4/transactions.json" => (404, vec![]),
+            _ => (200, b"statement".to_vec()),
+        })
+        .await;
+        let root = std::env::temp_dir().join(format!("fiocz-archive-probe-{}", std::process::id()));
+        let a =
+            StatementArchiver::new(server.fio("token"), &root, 2023).formats(&[ExportFormat::Json]);
+
+        let report = a.archive().await.unwrap();
+        assert_eq!(report.downloaded.len(), 2);
+        assert_eq!(report.gaps, [LastStatementId::new(2023, 3)]);
+        assert_eq!(a.completed_year(2023).unwrap(), Some(3));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,606 @@
+//! Bulk payment import from CSV spreadsheets
+//!
+//! [`CsvImporter`] maps spreadsheet columns onto order fields and turns each
+//! row into a [`DomesticTransaction`] or [`T2Transaction`]:
+//!
+//! - a Czech account (`prefix-number/bank`), or a Czech IBAN paid in CZK,
+//!   becomes a domestic order
+//! - any other IBAN becomes a euro order
+//!
+//! Rows that fail validation are reported with their line number instead of
+//! aborting the whole file, and [`CsvImport::preview`] summarises the valid
+//! orders before they are sent with [`crate::Fio::import_transactions`].
+//!
+//! ```
+//! use fiocz_rs::bulk::CsvImporter;
+//!
+//! let csv = "name,account,amount,vs\n\
+//!            Jan Novák,19-2000145399/0800,\"32 500,00\",202401\n\
+//!            Eva Dvořáková,2101179627/2010,\"28 750,50\",202401\n";
+//! let result = CsvImporter::new("2101179627").read(csv.as_bytes()).unwrap();
+//! assert!(result.errors.is_empty());
+//! assert_eq!(result.preview().to_string(), "2 orders: 61250.50 CZK");
+//! ```
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+
+use crate::error::{Error, FieldError};
+use crate::types::account_number::CzAccountNumber;
+use crate::types::currency::{Currency, Money};
+use crate::types::iban::Iban;
+use crate::types::transaction::{DomesticTransaction, Import, T2Transaction, Type};
+
+/// Order field filled from a CSV column
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Field {
+    /// Beneficiary name; used as the comment of domestic orders without one
+    Name,
+    /// Czech account number with bank code, or IBAN
+    Account,
+    /// Recipient bank BIC for euro orders
+    Bic,
+    /// Amount
+    Amount,
+    /// Currency, CZK for domestic and EUR for euro orders if absent
+    Currency,
+    /// Variable symbol
+    Vs,
+    /// Constant symbol
+    Ks,
+    /// Specific symbol
+    Ss,
+    /// Message for the recipient
+    Message,
+    /// Comment for the sender
+    Comment,
+    /// Due date in format YYYY-MM-DD
+    Date,
+}
+
+impl Field {
+    /// Header matched by the default mapping (case-insensitive)
+    #[must_use]
+    pub const fn default_header(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Account => "account",
+            Self::Bic => "bic",
+            Self::Amount => "amount",
+            Self::Currency => "currency",
+            Self::Vs => "vs",
+            Self::Ks => "ks",
+            Self::Ss => "ss",
+            Self::Message => "message",
+            Self::Comment => "comment",
+            Self::Date => "date",
+        }
+    }
+
+    const ALL: [Self; 11] = [
+        Self::Name,
+        Self::Account,
+        Self::Bic,
+        Self::Amount,
+        Self::Currency,
+        Self::Vs,
+        Self::Ks,
+        Self::Ss,
+        Self::Message,
+        Self::Comment,
+        Self::Date,
+    ];
+
+    const fn is_required(self) -> bool {
+        matches!(self, Self::Account | Self::Amount)
+    }
+}
+
+/// Location of a field in the CSV file
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Column {
+    /// Column with this header (case-insensitive)
+    Header(String),
+    /// Zero-based column index
+    Index(usize),
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header(name) => f.write_str(name),
+            Self::Index(i) => write!(f, "column {i}"),
+        }
+    }
+}
+
+/// Row that could not be turned into an order
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RowError {
+    /// Line number in the file (the header is line 1)
+    pub line: u64,
+    /// Problems found in the row
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<String> = self.errors.iter().map(ToString::to_string).collect();
+        write!(f, "line {}: {}", self.line, errors.join("; "))
+    }
+}
+
+/// Summary of the orders read from a file
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Preview {
+    /// Number of orders
+    pub count: usize,
+    /// Total amount per currency
+    pub totals: BTreeMap<Currency, Decimal>,
+}
+
+impl fmt::Display for Preview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let totals: Vec<String> = self
+            .totals
+            .iter()
+            .map(|(currency, total)| Money::new(*total, *currency).to_string())
+            .collect();
+        write!(f, "{} orders: {}", self.count, totals.join(", "))
+    }
+}
+
+/// Result of [`CsvImporter::read`]
+#[derive(Debug)]
+pub struct CsvImport {
+    /// Orders of all valid rows
+    pub import: Import,
+    /// Line number of each order in [`Import::orders`]
+    pub lines: Vec<u64>,
+    /// Rows that were skipped
+    pub errors: Vec<RowError>,
+}
+
+impl CsvImport {
+    /// Number of orders and total amount per currency
+    #[must_use]
+    pub fn preview(&self) -> Preview {
+        let mut preview = Preview::default();
+        for order in &self.import.orders {
+            let money = match order {
+                Type::Domestic(t) => t.money(),
+                Type::Euro(t) => t.money(),
+                Type::Foreign(t) => t.money(),
+            };
+            preview.count += 1;
+            *preview.totals.entry(money.currency()).or_default() += money.amount();
+        }
+        preview
+    }
+
+    /// Import of all rows, only if every row is valid
+    /// # Errors
+    /// * `Error::InvalidFields` - At least one row is invalid; the field names
+    ///   are prefixed with the line number
+    pub fn into_import(self) -> Result<Import, Error> {
+        if self.errors.is_empty() {
+            return Ok(self.import);
+        }
+        Err(Error::InvalidFields(
+            self.errors
+                .into_iter()
+                .flat_map(|row| {
+                    row.errors.into_iter().map(move |e| FieldError {
+                        field: e.field,
+                        message: format!("line {}: {}", row.line, e.message),
+                    })
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Symbols and due date, shared by both order builders
+macro_rules! apply_common {
+    ($builder:expr, $value:expr) => {{
+        let mut builder = $builder;
+        if let Some(vs) = $value(Field::Vs) {
+            builder = builder.vs(vs);
+        }
+        if let Some(ks) = $value(Field::Ks) {
+            builder = builder.ks(ks);
+        }
+        if let Some(ss) = $value(Field::Ss) {
+            builder = builder.ss(ss);
+        }
+        if let Some(date) = $value(Field::Date) {
+            builder = builder.date(date);
+        }
+        builder
+    }};
+}
+
+/// Reads payment orders from CSV files
+#[derive(Debug, Clone)]
+pub struct CsvImporter {
+    account_from: String,
+    columns: HashMap<Field, Column>,
+    delimiter: u8,
+    has_headers: bool,
+    decimal_comma: bool,
+    due_date: Option<String>,
+}
+
+impl CsvImporter {
+    /// Create new importer with the default column headers, see
+    /// [`Field::default_header`]
+    ///
+    /// The delimiter is `,` and amounts use a decimal comma, as exported by
+    /// Czech spreadsheets.
+    /// # Arguments
+    /// * `account_from` - Paying Fio account (`prefix-number`)
+    #[must_use]
+    pub fn new(account_from: &str) -> Self {
+        Self {
+            account_from: account_from.to_string(),
+            columns: Field::ALL
+                .into_iter()
+                .map(|f| (f, Column::Header(f.default_header().to_string())))
+                .collect(),
+            delimiter: b',',
+            has_headers: true,
+            decimal_comma: true,
+            due_date: None,
+        }
+    }
+
+    /// Read a field from another column
+    #[must_use]
+    pub fn column(mut self, field: Field, column: Column) -> Self {
+        self.columns.insert(field, column);
+        self
+    }
+
+    /// Set the field delimiter, e.g. `b';'`
+    #[must_use]
+    pub const fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set whether the first line holds headers; without headers all columns
+    /// must be given as [`Column::Index`]
+    #[must_use]
+    pub const fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Set whether amounts use a decimal comma (`1 234,50`) instead of a
+    /// decimal point (`1234.50`)
+    ///
+    /// With a decimal comma, a point is accepted only as a thousands
+    /// separator (`1.234,50`); a value such as `1250.50` is reported as an
+    /// invalid row rather than guessed.
+    #[must_use]
+    pub const fn decimal_comma(mut self, decimal_comma: bool) -> Self {
+        self.decimal_comma = decimal_comma;
+        self
+    }
+
+    /// Set the due date of rows without one, instead of the day the file is
+    /// read
+    ///
+    /// Pin the date when sending through a
+    /// [`LedgerImporter`](crate::ledger::LedgerImporter): the date is part of
+    /// the import fingerprint, so a file read again on a later day would
+    /// otherwise not be recognised as already submitted.
+    #[must_use]
+    pub fn due_date(mut self, date: NaiveDate) -> Self {
+        self.due_date = Some(date.to_string());
+        self
+    }
+
+    /// Read all rows of a CSV file
+    /// # Arguments
+    /// * `reader` - CSV data
+    /// # Returns
+    /// * `CsvImport` - Orders of the valid rows and errors of the others
+    /// # Errors
+    /// * `Error::Csv` - The file is not valid CSV
+    /// * `Error::MissingColumn` - A mandatory column is not in the header
+    pub fn read(&self, reader: impl io::Read) -> Result<CsvImport, Error> {
+        let mut csv = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_headers)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        let indices = self.indices(&mut csv)?;
+        let mut result = CsvImport {
+            import: Import::new(),
+            lines: vec![],
+            errors: vec![],
+        };
+        for record in csv.records() {
+            let record = record?;
+            let line = record.position().map_or(0, csv::Position::line);
+            let value = |field: Field| {
+                indices
+                    .get(&field)
+                    .and_then(|i| record.get(*i))
+                    .filter(|v| !v.is_empty())
+                    .or_else(|| self.due_date.as_deref().filter(|_| field == Field::Date))
+            };
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            match self.order(value) {
+                Ok(order) => {
+                    result.import.orders.push(order);
+                    result.lines.push(line);
+                }
+                Err(errors) => result.errors.push(RowError { line, errors }),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Column index of every mapped field present in the file
+    fn indices<R: io::Read>(
+        &self,
+        csv: &mut csv::Reader<R>,
+    ) -> Result<HashMap<Field, usize>, Error> {
+        let headers: Vec<String> = if self.has_headers {
+            csv.headers()?.iter().map(str::to_lowercase).collect()
+        } else {
+            vec![]
+        };
+        let mut indices = HashMap::new();
+        // Fixed order, so the same file always reports the same missing column
+        let mapped = Field::ALL
+            .iter()
+            .filter_map(|field| Some((field, self.columns.get(field)?)));
+        for (field, column) in mapped {
+            let index = match column {
+                Column::Index(i) => Some(*i),
+                Column::Header(name) => headers.iter().position(|h| *h == name.to_lowercase()),
+            };
+            match index {
+                Some(i) => {
+                    indices.insert(*field, i);
+                }
+                None if field.is_required() => {
+                    return Err(Error::MissingColumn(column.to_string()));
+                }
+                None => {}
+            }
+        }
+        Ok(indices)
+    }
+
+    /// Order for one row
+    fn order<'a>(&self, value: impl Fn(Field) -> Option<&'a str>) -> Result<Type, Vec<FieldError>> {
+        let field_error = |field, message: String| vec![FieldError { field, message }];
+        let amount = value(Field::Amount)
+            .ok_or_else(|| field_error("amount", "is mandatory".to_string()))
+            .and_then(|a| {
+                self.amount(a)
+                    .ok_or_else(|| field_error("amount", format!("{a:?} is not a number")))
+            })?;
+        let account = value(Field::Account)
+            .ok_or_else(|| field_error("accountTo", "is mandatory".to_string()))?;
+        let currency = value(Field::Currency)
+            .map(str::parse::<Currency>)
+            .transpose()
+            .map_err(|e| field_error("currency", e.to_string()))?;
+
+        let domestic = if account.contains('/') {
+            Some(account.to_string())
+        } else {
+            let iban: Iban = account
+                .parse()
+                .map_err(|e: Error| field_error("accountTo", e.to_string()))?;
+            match (iban.country(), currency) {
+                ("CZ", None | Some(Currency::Czk)) => Some(
+                    iban.to_cz_account()
+                        .as_ref()
+                        .map(CzAccountNumber::to_string)
+                        .map_err(|e| field_error("accountTo", e.to_string()))?,
+                ),
+                _ => None,
+            }
+        };
+
+        let result = if let Some(account) = domestic {
+            let mut builder = DomesticTransaction::builder()
+                .account_from(&self.account_from)
+                .amount(amount)
+                .account_to(&account);
+            if let Some(currency) = currency {
+                builder = builder.currency(currency);
+            }
+            if let Some(comment) = value(Field::Comment).or_else(|| value(Field::Name)) {
+                builder = builder.comment(comment);
+            }
+            if let Some(message) = value(Field::Message) {
+                builder = builder.message_for_recipient(message);
+            }
+            builder = apply_common!(builder, value);
+            builder.build().map(Type::Domestic)
+        } else {
+            if currency.is_some_and(|c| c != Currency::Eur) {
+                return Err(field_error(
+                    "currency",
+                    "euro orders must be in EUR".to_string(),
+                ));
+            }
+            let mut builder = T2Transaction::builder()
+                .account_from(&self.account_from)
+                .amount(amount)
+                .account_to(account)
+                .benef_name(value(Field::Name).unwrap_or_default());
+            if let Some(bic) = value(Field::Bic) {
+                builder = builder.bic(bic);
+            }
+            if let Some(comment) = value(Field::Comment) {
+                builder = builder.comment(comment);
+            }
+            if let Some(message) = value(Field::Message) {
+                builder = builder.remittance_info1(message);
+            }
+            builder = apply_common!(builder, value);
+            builder.build().map(Type::Euro)
+        };
+        result.map_err(|e| match e {
+            Error::InvalidFields(errors) => errors,
+            e => field_error("row", e.to_string()),
+        })
+    }
+
+    fn amount(&self, value: &str) -> Option<Decimal> {
+        let mut value: String = value
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '\u{a0}')
+            .collect();
+        if self.decimal_comma {
+            let (integer, fraction) = value.split_once(',').unwrap_or((&value, ""));
+            let mut groups = integer.split('.');
+            let first = groups.next()?.trim_start_matches(['-', '+']);
+            let thousands = |group: &str| group.len() == 3;
+            if fraction.contains('.')
+                || (integer.contains('.') && (first.len() > 3 || !groups.all(thousands)))
+            {
+                return None;
+            }
+            value = value.replace('.', "").replace(',', ".");
+        }
+        value.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAYROLL: &str = "Jméno;Účet;Částka;VS\n\
+                           Jan Novák;19-2000145399/0800;32 500,00;202401\n\
+                           Eva Dvořáková;124/0800;1 000,00;202401\n\
+                           Hans Mueller;DE89370400440532013000;1.250,00;\n\
+                           Petr Malý;CZ6508000000192000145399;abc;202401\n\
+                           ;;;\n\
+                           Anna Horká;2101179627/2010;500;12345678901\n";
+
+    fn importer() -> CsvImporter {
+        CsvImporter::new("2101179627")
+            .delimiter(b';')
+            .column(Field::Name, Column::Header("Jméno".to_string()))
+            .column(Field::Account, Column::Header("ÚČET".to_string()))
+            .column(Field::Amount, Column::Index(2))
+            .column(Field::Vs, Column::Header("VS".to_string()))
+    }
+
+    #[test]
+    fn rows_to_orders() {
+        let result = importer().read(PAYROLL.as_bytes()).unwrap();
+        assert_eq!(result.lines, [2, 4]);
+        let Type::Domestic(salary) = &result.import.orders[0] else {
+            panic!("expected domestic order");
+        };
+        assert_eq!(salary.account_to, "19-2000145399");
+        assert_eq!(salary.amount, Decimal::new(32500, 0));
+        assert_eq!(salary.comment.as_deref(), Some("Jan Novák"));
+        let Type::Euro(euro) = &result.import.orders[1] else {
+            panic!("expected euro order");
+        };
+        assert_eq!(euro.benef_name, "Hans Mueller");
+        assert_eq!(euro.amount, Decimal::new(1250, 0));
+    }
+
+    #[test]
+    fn row_errors_with_lines() {
+        let result = importer().read(PAYROLL.as_bytes()).unwrap();
+        let lines: Vec<u64> = result.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, [3, 5, 7]);
+        assert_eq!(result.errors[0].errors[0].field, "accountTo");
+        assert_eq!(result.errors[1].errors[0].field, "amount");
+        assert_eq!(result.errors[2].errors[0].field, "vs");
+        assert!(result.errors[1].to_string().starts_with("line 5: amount:"));
+        assert!(matches!(
+            result.into_import(),
+            Err(Error::InvalidFields(errors)) if errors.len() == 3
+        ));
+    }
+
+    #[test]
+    fn preview_totals() {
+        let preview = importer().read(PAYROLL.as_bytes()).unwrap().preview();
+        assert_eq!(preview.count, 2);
+        assert_eq!(preview.totals[&Currency::Czk], Decimal::new(32500, 0));
+        assert_eq!(preview.totals[&Currency::Eur], Decimal::new(1250, 0));
+        assert_eq!(preview.to_string(), "2 orders: 32500.00 CZK, 1250.00 EUR");
+    }
+
+    #[test]
+    fn decimal_comma_amounts() {
+        let importer = importer();
+        let amount = |value| importer.amount(value);
+        assert_eq!(amount("1.250,50"), Some(Decimal::new(125_050, 2)));
+        assert_eq!(amount("1.250.000"), Some(Decimal::new(1_250_000, 0)));
+        assert_eq!(amount("-12,5"), Some(Decimal::new(-125, 1)));
+        assert_eq!(amount("1250.50"), None);
+        assert_eq!(amount("1.25"), None);
+        assert_eq!(amount("1250.500,00"), None);
+        assert_eq!(amount("1,250.00"), None);
+        assert_eq!(amount("12,5,0"), None);
+
+        let csv = "Jméno;Účet;Částka;VS\nJan;2101179627/2010;1250.50;\n";
+        let import = importer.read(csv.as_bytes()).unwrap();
+        assert!(import.import.orders.is_empty());
+        assert_eq!(import.errors[0].errors[0].field, "amount");
+        let point = CsvImporter::new("2101179627").decimal_comma(false);
+        assert_eq!(point.amount("1250.50"), Some(Decimal::new(125_050, 2)));
+    }
+
+    #[test]
+    fn missing_column() {
+        let csv = "name,amount\nJan,100\n";
+        assert!(matches!(
+            CsvImporter::new("2101179627").read(csv.as_bytes()),
+            Err(Error::MissingColumn(_))
+        ));
+        let without_headers = CsvImporter::new("2101179627")
+            .has_headers(false)
+            .decimal_comma(false)
+            .column(Field::Account, Column::Index(0))
+            .column(Field::Amount, Column::Index(1))
+            .read("2101179627/2010,100.50\n".as_bytes())
+            .unwrap();
+        assert_eq!(without_headers.lines, [1]);
+
+        let neither = "name,vs\nJan,1\n";
+        for _ in 0..10 {
+            let error = CsvImporter::new("2101179627")
+                .read(neither.as_bytes())
+                .unwrap_err();
+            assert!(matches!(error, Error::MissingColumn(column) if column == "account"));
+        }
+    }
+
+    #[test]
+    fn hostile_cell_stays_in_its_order() {
+        let csv = "Jméno;Účet;Částka;VS;Zpráva\n\
+                   Jan;2101179627/2010;100;;</messageForRecipient></DomesticTransaction>\
+                   <DomesticTransaction><amount>99999</amount>\n";
+        let result = importer()
+            .column(Field::Message, Column::Header("Zpráva".to_string()))
+            .read(csv.as_bytes())
+            .unwrap();
+        assert!(result.errors.is_empty());
+        let xml = result.into_import().unwrap().to_xml();
+        assert_eq!(xml.matches("<DomesticTransaction>").count(), 1);
+        assert_eq!(xml.matches("<amount>").count(), 1);
+    }
+}
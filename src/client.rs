@@ -1,4 +1,4 @@
-use log::{debug, error, warn};
+use log::{debug, error, info, warn};
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 use std::time::Instant;
@@ -7,12 +7,21 @@ use crate::error::Error;
 use crate::Fio;
 use crate::MIN_REQUEST_INTERVAL;
 
-fn map_status_error(status: StatusCode) -> Option<Error> {
+/// Root of the Fio REST API
+pub(crate) const API_URL: &str = "https://fioapi.fio.cz/v1/rest";
+
+/// Response returned by imports in dry-run mode
+pub(crate) const DRY_RUN_IMPORT_RESPONSE: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+<responseImport><result><errorCode>0</errorCode><idInstruction>dry-run</idInstruction>\
+<status>ok</status></result></responseImport>";
+
+pub(crate) fn map_status_error(status: StatusCode) -> Option<Error> {
     match status {
         StatusCode::CONFLICT => Some(Error::Limit),
         StatusCode::INTERNAL_SERVER_ERROR => Some(Error::Malformed),
         StatusCode::PAYLOAD_TOO_LARGE => Some(Error::TooLarge),
-        StatusCode::NOT_FOUND => Some(Error::Token),
+        StatusCode::NOT_FOUND => Some(Error::InvalidToken),
+        StatusCode::FORBIDDEN => Some(Error::InsufficientRights),
         StatusCode::UNPROCESSABLE_ENTITY => Some(Error::HistoricalDataLocked),
         _ => None,
     }
@@ -52,68 +61,96 @@ impl Fio {
         }
     }
 
-    pub(crate) async fn api_get_text(&self, rest_method: &str) -> Result<String, Error> {
+    /// Send a GET request and map error statuses
+    async fn api_get_response(&self, rest_method: &str) -> Result<reqwest::Response, Error> {
         self.enforce_rate_limit().await;
-        match reqwest::get(format!("https://fioapi.fio.cz/v1/rest/{rest_method}")).await {
-            Ok(resp) => {
-                if let Some(e) = map_status_error(resp.status()) {
-                    return Err(e);
-                }
-                match resp.text().await {
-                    Ok(v) => Ok(v),
-                    Err(e) => Err(e.into()),
-                }
-            }
-            Err(e) => Err(e.into()),
+        let resp = reqwest::get(self.url(rest_method)).await?;
+        match map_status_error(resp.status()) {
+            Some(e) => Err(e),
+            None => Ok(resp),
         }
     }
 
+    pub(crate) async fn api_get_text(&self, rest_method: &str) -> Result<String, Error> {
+        Ok(self.api_get_response(rest_method).await?.text().await?)
+    }
+
+    pub(crate) async fn api_get_bytes(&self, rest_method: &str) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .api_get_response(rest_method)
+            .await?
+            .bytes()
+            .await?
+            .to_vec())
+    }
+
     pub(crate) async fn api_post(
         &self,
         rest_method: &str,
         import_type: &str,
         body: String,
     ) -> Result<String, Error> {
+        let (status, text) = self
+            .api_post_response(rest_method, import_type, body)
+            .await?;
+        match map_status_error(status) {
+            Some(e) => Err(e),
+            None => Ok(text),
+        }
+    }
+
+    /// Upload a file and return the status with the body, whatever the status
+    pub(crate) async fn api_post_response(
+        &self,
+        rest_method: &str,
+        import_type: &str,
+        body: String,
+    ) -> Result<(StatusCode, String), Error> {
         self.enforce_rate_limit().await;
         let client = reqwest::Client::new();
+        let file = reqwest::multipart::Part::text(body)
+            .file_name("import.xml")
+            .mime_str("application/xml")?;
         let form = reqwest::multipart::Form::new()
             .text("token", self.token.clone())
             .text("type", import_type.to_string())
-            .part(
-                "file",
-                match reqwest::multipart::Part::text(body)
-                    .file_name("import.xml")
-                    .mime_str("application/xml")
-                {
-                    Ok(file) => file,
-                    Err(e) => {
-                        return Err(e.into());
-                    }
-                },
-            );
-        match client
-            .post(format!("https://fioapi.fio.cz/v1/rest/{rest_method}"))
+            .part("file", file);
+        let resp = client
+            .post(self.url(rest_method))
             .multipart(form)
             .send()
-            .await
-        {
-            Ok(resp) => {
-                if let Some(e) = map_status_error(resp.status()) {
-                    return Err(e);
-                }
-                match resp.text().await {
-                    Ok(v) => Ok(v),
-                    Err(e) => Err(e.into()),
-                }
-            }
-            Err(e) => Err(e.into()),
-        }
+            .await?;
+        let status = resp.status();
+        Ok((status, resp.text().await?))
     }
 
     pub(crate) async fn api_get_empty(&self, rest_method: &str) -> Result<(), Error> {
+        if self.dry_run {
+            self.log_dry_run("GET", rest_method, None);
+            return Ok(());
+        }
         match self.api_get_text(rest_method).await {
             Ok(_) => Ok(()),
             Err(e) => Err(e),
         }
     }
+
+    /// Request URL of a REST method
+    fn url(&self, rest_method: &str) -> String {
+        format!("{}/{rest_method}", self.base_url)
+    }
+
+    /// Request URL with the token replaced by `<token>`
+    pub(crate) fn redacted_url(&self, rest_method: &str) -> String {
+        self.url(rest_method).replace(&self.token, "<token>")
+    }
+
+    /// Log a request that dry-run mode does not send, with the token redacted
+    pub(crate) fn log_dry_run(&self, method: &str, rest_method: &str, body: Option<&str>) {
+        let url = self.redacted_url(rest_method);
+        match body {
+            Some(body) => info!("Dry run, not sending {method} {url}\n{body}"),
+            None => info!("Dry run, not sending {method} {url}"),
+        }
+    }
 }
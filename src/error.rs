@@ -15,7 +15,10 @@ pub enum Error {
     Limit,
     /// Invalid token
     #[error("The token does not exist or is deactivated")]
-    Token,
+    InvalidToken,
+    /// Token lacks the permission for the operation (e.g. import with a read-only token)
+    #[error("The token does not have permission for this operation")]
+    InsufficientRights,
     /// Malformed request
     #[error("The request was malformed")]
     Malformed,
@@ -34,4 +37,123 @@ pub enum Error {
     /// Historical data access requires strong authorization (data older than 90 days)
     #[error("Historical data access requires strong authorization (data older than 90 days)")]
     HistoricalDataLocked,
+    /// I/O error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Local checkpoint could not be parsed
+    #[error("Corrupt checkpoint: {0}")]
+    CorruptCheckpoint(String),
+    /// Import ledger journal could not be parsed
+    #[error("Corrupt import ledger: {0}")]
+    CorruptLedger(String),
+    /// Import cannot be approved or submitted through the approval queue
+    #[error("Approval: {0}")]
+    Approval(String),
+    /// Import already submitted, or sent with unknown outcome
+    #[error("Duplicate import: {0}")]
+    DuplicateImport(String),
+    /// Error of a request shared by several accounts registered with the same
+    /// token in [`crate::accounts::FioAccounts`], see [`Error::root`]
+    #[error(transparent)]
+    Shared(std::sync::Arc<Error>),
+    /// Movement handler failed
+    #[error("Movement handler failed: {0}")]
+    Handler(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// Statements of different accounts cannot be merged
+    #[error("Cannot merge statements of different accounts: {0} and {1}")]
+    AccountMismatch(String, String),
+    /// Merged statements leave days between them uncovered
+    #[error("Cannot merge statements with a gap between {0} and {1}")]
+    MergeGap(String, String),
+    /// Movements with the same ID differ between merged statements
+    #[error("Conflicting versions of movements {0:?}")]
+    MergeConflict(Vec<i64>),
+    /// Order builder rejected one or more fields
+    #[error("Invalid fields: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    InvalidFields(Vec<FieldError>),
+    /// Invalid Czech account number
+    #[error("Invalid account number: {0}")]
+    InvalidAccountNumber(String),
+    /// Bank code not in the ČNB bank code list
+    #[error("Unknown bank code: {0}")]
+    UnknownBankCode(String),
+    /// Unknown ISO 4217 currency code
+    #[error("Invalid currency: {0}")]
+    InvalidCurrency(String),
+    /// Amount not representable in its currency
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+    /// Payment reason is not a three digit code
+    #[error("Invalid payment reason: {0}")]
+    InvalidPaymentReason(String),
+    /// Invalid variable, constant or specific symbol
+    #[error("Invalid symbol: {0}")]
+    InvalidSymbol(String),
+    /// Invalid IBAN
+    #[error("Invalid IBAN: {0}")]
+    InvalidIban(String),
+    /// Invalid BIC
+    #[error("Invalid BIC: {0}")]
+    InvalidBic(String),
+    /// Invalid SPAYD payment descriptor
+    #[error("Invalid SPAYD: {0}")]
+    InvalidSpayd(String),
+    /// Invalid EPC QR payload
+    #[error("Invalid EPC payload: {0}")]
+    InvalidEpc(String),
+    /// CSV parsing error
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// Mandatory CSV column not found
+    #[error("Missing CSV column: {0}")]
+    MissingColumn(String),
+    /// QR code cannot hold the payload
+    #[cfg(feature = "qr")]
+    #[error(transparent)]
+    QrCode(#[from] qrcode::types::QrError),
+    /// QR code image cannot be encoded
+    #[cfg(feature = "qr")]
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    /// SQLite error
+    #[cfg(feature = "sqlite")]
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+impl Error {
+    /// The error itself, or the error behind [`Error::Shared`]
+    ///
+    /// Match on this to handle an error the same way whether or not the
+    /// request was shared by several accounts.
+    ///
+    /// ```
+    /// use fiocz_rs::error::Error;
+    /// use std::sync::Arc;
+    ///
+    /// let shared = Error::Shared(Arc::new(Error::Limit));
+    /// assert!(matches!(shared.root(), Error::Limit));
+    /// ```
+    #[must_use]
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Shared(error) => error.root(),
+            error => error,
+        }
+    }
+}
+
+/// Problem with a single field reported by an order builder
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FieldError {
+    /// Field name as used in the import XML
+    pub field: &'static str,
+    /// Human readable description
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
 }
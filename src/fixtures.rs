@@ -0,0 +1,148 @@
+//! Test data shared by the unit tests of several modules
+use rust_decimal::Decimal;
+
+use crate::types::account_statement::Statement;
+use crate::types::transaction::{DomesticTransaction, Import, T2Transaction, Type};
+
+/// Domestic order of `amount` CZK to 19-2000145399/0800, due on 2030-01-01
+pub(crate) fn domestic(amount: i64) -> DomesticTransaction {
+    DomesticTransaction::builder()
+        .account_from("2101179627")
+        .amount(Decimal::new(amount, 0))
+        .account_to("19-2000145399/0800")
+        .date("2030-01-01")
+        .build()
+        .unwrap()
+}
+
+/// Euro order of `amount` EUR to a German IBAN, due on 2030-01-01
+pub(crate) fn euro(amount: i64) -> T2Transaction {
+    T2Transaction::builder()
+        .account_from("2101179627")
+        .amount(Decimal::new(amount, 0))
+        .account_to("DE89370400440532013000")
+        .benef_name("Muster GmbH")
+        .date("2030-01-01")
+        .build()
+        .unwrap()
+}
+
+/// Import holding a single [`domestic`] order
+pub(crate) fn domestic_import(amount: i64) -> Import {
+    Import {
+        orders: vec![Type::Domestic(domestic(amount))],
+    }
+}
+
+/// Movement in the API's JSON form, in CZK
+pub(crate) fn movement_json(id: i64, date: &str, amount: &str, comment: &str) -> String {
+    format!(
+        r#"{{
+            "column22": {{"value": {id}, "name": "ID pohybu", "id": 22}},
+            "column0": {{"value": "{date}+0100", "name": "Datum", "id": 0}},
+            "column1": {{"value": {amount}, "name": "Objem", "id": 1}},
+            "column14": {{"value": "CZK", "name": "Měna", "id": 14}},
+            "column25": {{"value": "{comment}", "name": "Komentář", "id": 25}},
+            "column5": null
+        }}"#
+    )
+}
+
+/// Statement of a CZK account in the API's JSON form
+///
+/// Dates are `YYYY-MM-DD`; `last_download` is the bookmark before the
+/// download (`idLastDownload`).
+pub(crate) struct StatementJson<'a> {
+    pub(crate) account: &'a str,
+    pub(crate) start: &'a str,
+    pub(crate) end: &'a str,
+    pub(crate) opening: &'a str,
+    pub(crate) closing: &'a str,
+    pub(crate) last_download: Option<i64>,
+    pub(crate) movements: Vec<String>,
+}
+
+impl Default for StatementJson<'_> {
+    fn default() -> Self {
+        Self {
+            account: "2101179627",
+            start: "2024-01-15",
+            end: "2024-01-15",
+            opening: "0",
+            closing: "0",
+            last_download: None,
+            movements: vec![],
+        }
+    }
+}
+
+impl StatementJson<'_> {
+    /// The statement as JSON text
+    pub(crate) fn json(&self) -> String {
+        let Self {
+            account,
+            start,
+            end,
+            opening,
+            closing,
+            ..
+        } = self;
+        format!(
+            r#"{{"accountStatement": {{
+                "info": {{
+                    "accountId": "{account}", "bankId": "2010", "currency": "CZK",
+                    "iban": "CZ5020100000002101179627", "bic": "FIOBCZPPXXX",
+                    "openingBalance": {opening}, "closingBalance": {closing},
+                    "dateStart": "{start}+0100", "dateEnd": "{end}+0100",
+                    "yearList": null, "idList": null, "idFrom": null, "idTo": null,
+                    "idLastDownload": {}
+                }},
+                "transactionList": {{"transaction": [{}]}}
+            }}}}"#,
+            self.last_download
+                .map_or("null".to_string(), |id| id.to_string()),
+            self.movements.join(",")
+        )
+    }
+
+    /// The statement parsed
+    pub(crate) fn parse(&self) -> Statement {
+        serde_json::from_str(&self.json()).unwrap()
+    }
+}
+
+/// Parsed statement of a CZK account, see [`StatementJson`]
+pub(crate) fn statement(
+    account: &str,
+    start: &str,
+    end: &str,
+    opening: &str,
+    closing: &str,
+    movements: &[String],
+) -> Statement {
+    StatementJson {
+        account,
+        start,
+        end,
+        opening,
+        closing,
+        last_download: None,
+        movements: movements.to_vec(),
+    }
+    .parse()
+}
+
+/// Statement JSON with movements `ids` and the bookmark before download,
+/// as returned by `last/{token}/transactions.json`
+pub(crate) fn last_json(ids: &[i64], last_download: Option<i64>) -> Vec<u8> {
+    StatementJson {
+        last_download,
+        movements: ids
+            .iter()
+            .map(|id| movement_json(*id, "2024-01-15", "100.0", ""))
+            .collect(),
+        ..StatementJson::default()
+    }
+    .json()
+    .into_bytes()
+}
@@ -0,0 +1,621 @@
+//! Idempotent import submission
+//!
+//! When the connection drops after an import was sent, there is no way to
+//! tell whether the bank accepted it, and sending it again may pay twice.
+//! [`LedgerImporter`] fingerprints every [`Import`] and records it in an
+//! [`ImportLedger`] journal before sending, then records the outcome. An
+//! import whose fingerprint was already submitted, or whose outcome is
+//! unknown, is refused (or only logged, see [`Resubmission`]).
+//! [`LedgerImporter::import_in_batches`] does the same for every batch, so
+//! an interrupted batch import can be run again to send the rest.
+//!
+//! The order dates are part of the fingerprint. The builders and
+//! [`CsvImporter`](crate::bulk::CsvImporter) default the date to today, so
+//! an import rebuilt on a later day looks new and is sent again; pin the
+//! dates (e.g. with [`CsvImporter::due_date`](crate::bulk::CsvImporter::due_date))
+//! when an import may be rebuilt and rerun.
+//!
+//! ```no_run
+//! use fiocz_rs::ledger::{ImportLedger, LedgerImporter, Resubmission};
+//! use fiocz_rs::types::transaction::Import;
+//! use fiocz_rs::Fio;
+//!
+//! # async fn run(import: Import) -> Result<(), fiocz_rs::error::Error> {
+//! let importer = LedgerImporter::new(
+//!     Fio::new("your-api-token"),
+//!     ImportLedger::new("imports.jsonl"),
+//!     Resubmission::Refuse,
+//! );
+//! let response = importer.import(import).await?;
+//! println!("Accepted as {:?}", response.id_instruction);
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::Local;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::types::import_response::ImportResponse;
+use crate::types::transaction::{BatchImportReport, BatchLimits, Import};
+use crate::Fio;
+
+/// SHA-256 of the XML sent for an import, as lowercase hex
+///
+/// Two imports with the same orders in the same order have the same
+/// fingerprint. The order dates count, so the same orders defaulted to
+/// today on different days differ.
+#[must_use]
+pub fn fingerprint(import: &Import) -> String {
+    Sha256::digest(import.to_xml().as_bytes()).iter().fold(
+        String::with_capacity(64),
+        |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        },
+    )
+}
+
+/// State of an import in the ledger
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedgerState {
+    /// About to be sent; if this is the last state the outcome is unknown
+    Pending,
+    /// Accepted by the bank
+    Submitted,
+    /// Rejected by the bank, safe to send again
+    Failed,
+}
+
+/// Journal line recording a state change of an import
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Import fingerprint, see [`fingerprint`]
+    pub fingerprint: String,
+    /// New state
+    pub state: LedgerState,
+    /// Instruction ID assigned by the bank
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_instruction: Option<String>,
+    /// Number of orders in the import
+    pub orders: usize,
+    /// Time of the change (RFC 3339)
+    pub recorded_at: String,
+}
+
+/// Append-only journal of import submissions, one JSON object per line
+///
+/// Each line is flushed to disk before the import is sent, so the journal
+/// survives a crash mid-request. The last line of a fingerprint wins. A line
+/// cut short by a crash is skipped with a warning and its import, if it can
+/// be told, is treated as [`LedgerState::Pending`]. The journal is not
+/// locked; use one per process.
+#[derive(Debug, Clone)]
+pub struct ImportLedger {
+    path: PathBuf,
+}
+
+impl ImportLedger {
+    /// Create new ledger
+    /// # Arguments
+    /// * `path` - Path of the journal file, created on first record
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Latest entry of every fingerprint
+    /// # Errors
+    /// * `Error::Io` - The journal could not be read
+    /// * `Error::CorruptLedger` - A line is not a ledger entry
+    pub fn entries(&self) -> Result<HashMap<String, LedgerEntry>, Error> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries: HashMap<String, LedgerEntry> = HashMap::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<LedgerEntry>(line) {
+                Ok(entry) => {
+                    entries.insert(entry.fingerprint.clone(), entry);
+                }
+                // A crash while appending leaves an incomplete object
+                Err(e) if e.is_eof() => {
+                    warn!("Skipping truncated ledger line: {line}");
+                    if let Some(fingerprint) = truncated_fingerprint(line) {
+                        let entry =
+                            entries
+                                .entry(fingerprint.to_string())
+                                .or_insert_with(|| LedgerEntry {
+                                    fingerprint: fingerprint.to_string(),
+                                    state: LedgerState::Pending,
+                                    id_instruction: None,
+                                    orders: 0,
+                                    recorded_at: String::new(),
+                                });
+                        entry.state = LedgerState::Pending;
+                    }
+                }
+                Err(e) => return Err(Error::CorruptLedger(format!("{e}: {line}"))),
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Latest entry of a fingerprint
+    /// # Errors
+    /// * `Error::Io` / `Error::CorruptLedger` - The journal could not be read
+    pub fn get(&self, fingerprint: &str) -> Result<Option<LedgerEntry>, Error> {
+        Ok(self.entries()?.remove(fingerprint))
+    }
+
+    /// Append a state change, e.g. to resolve a pending import after checking
+    /// it in internet banking
+    /// # Errors
+    /// * `Error::Io` - The journal could not be written
+    pub fn record(
+        &self,
+        fingerprint: &str,
+        state: LedgerState,
+        id_instruction: Option<String>,
+        orders: usize,
+    ) -> Result<LedgerEntry, Error> {
+        let entry = LedgerEntry {
+            fingerprint: fingerprint.to_string(),
+            state,
+            id_instruction,
+            orders,
+            recorded_at: Local::now().to_rfc3339(),
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        // Start on a new line if the previous write was cut short
+        let truncated = std::fs::read(&self.path)?
+            .last()
+            .is_some_and(|b| *b != b'\n');
+        if truncated {
+            writeln!(file)?;
+        }
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        file.sync_all()?;
+        Ok(entry)
+    }
+}
+
+/// What to do with an import already in the ledger
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Resubmission {
+    /// Fail with [`Error::DuplicateImport`]
+    #[default]
+    Refuse,
+    /// Log a warning and send it anyway
+    Warn,
+}
+
+/// Sends imports at most once, tracked by an [`ImportLedger`]
+pub struct LedgerImporter {
+    fio: Fio,
+    ledger: ImportLedger,
+    resubmission: Resubmission,
+}
+
+impl LedgerImporter {
+    /// Create new importer
+    /// # Arguments
+    /// * `fio` - API client
+    /// * `ledger` - Journal of submissions
+    /// * `resubmission` - Handling of imports already in the ledger
+    #[must_use]
+    pub const fn new(fio: Fio, ledger: ImportLedger, resubmission: Resubmission) -> Self {
+        Self {
+            fio,
+            ledger,
+            resubmission,
+        }
+    }
+
+    /// Journal of submissions
+    #[must_use]
+    pub const fn ledger(&self) -> &ImportLedger {
+        &self.ledger
+    }
+
+    /// Whether the client only logs imports, see [`Fio::with_dry_run`]
+    #[must_use]
+    pub const fn is_dry_run(&self) -> bool {
+        self.fio.is_dry_run()
+    }
+
+    /// Send an import unless an identical one was submitted before
+    ///
+    /// The import is recorded as [`LedgerState::Pending`] before sending. A
+    /// response from the bank turns it into [`LedgerState::Submitted`] or
+    /// [`LedgerState::Failed`]; a network failure or unreadable response
+    /// leaves it pending, and it is refused until resolved with
+    /// [`ImportLedger::record`]. In dry-run mode nothing is recorded.
+    /// # Arguments
+    /// * `import` - Transactions to import
+    /// # Returns
+    /// * `ImportResponse` - Parsed bank response, also when the bank rejected
+    ///   the import
+    /// # Errors
+    /// * `Error::DuplicateImport` - The import was already submitted or its
+    ///   outcome is unknown
+    /// * `Error::Io` / `Error::CorruptLedger` - Ledger failure
+    /// * `Error::Limit` / `Error::Reqwest` / ... - The request failed
+    pub async fn import(&self, import: Import) -> Result<ImportResponse, Error> {
+        let fingerprint = fingerprint(&import);
+        let orders = import.orders.len();
+        self.check(&fingerprint)?;
+        if self.fio.is_dry_run() {
+            return ImportResponse::from_xml(&self.fio.import_transactions(import).await?);
+        }
+        self.ledger
+            .record(&fingerprint, LedgerState::Pending, None, orders)?;
+
+        let response = match self.fio.import_transactions(import).await {
+            Ok(xml) => ImportResponse::from_xml(&xml),
+            Err(e) => Err(e),
+        };
+        match response {
+            Ok(response) => {
+                let state = if response.is_ok() {
+                    LedgerState::Submitted
+                } else {
+                    LedgerState::Failed
+                };
+                self.ledger
+                    .record(&fingerprint, state, response.id_instruction.clone(), orders)?;
+                info!("Import {fingerprint} recorded as {state:?}");
+                Ok(response)
+            }
+            Err(e) if bank_refused(&e) => {
+                self.ledger
+                    .record(&fingerprint, LedgerState::Failed, None, orders)?;
+                Err(e)
+            }
+            Err(e) => {
+                warn!("Outcome of import {fingerprint} is unknown, left pending: {e}");
+                Err(e)
+            }
+        }
+    }
+
+    /// Send an import in batches, each sent at most once
+    ///
+    /// Like [`Fio::import_in_batches`], with every batch going through
+    /// [`Self::import`]. With [`Resubmission::Refuse`], batches the ledger
+    /// records as submitted are skipped and their orders reported as
+    /// [`OrderStatus::AlreadySubmitted`](crate::types::transaction::OrderStatus::AlreadySubmitted),
+    /// so an interrupted run can be repeated with the same import and limits
+    /// to send the remaining batches. A batch with unknown outcome stops the
+    /// run with [`Error::DuplicateImport`] in [`BatchImportReport::error`].
+    /// # Arguments
+    /// * `import` - Transactions to import
+    /// * `limits` - Maximum orders and bytes per batch
+    /// # Returns
+    /// * `BatchImportReport` - Bank responses and the result of each order
+    /// # Errors
+    /// * `Error::InsufficientRights` - The token is read-only
+    /// * `Error::Io` / `Error::CorruptLedger` - The ledger could not be read
+    pub async fn import_in_batches(
+        &self,
+        import: Import,
+        limits: BatchLimits,
+    ) -> Result<BatchImportReport, Error> {
+        self.fio.check_import_permission()?;
+        let batches = import.into_batches(&limits);
+        let mut report = BatchImportReport::new(&batches);
+        for (number, batch) in batches.iter().enumerate() {
+            if self.resubmission == Resubmission::Refuse {
+                let submitted = self
+                    .ledger
+                    .get(&fingerprint(&batch.import))?
+                    .filter(|entry| entry.state == LedgerState::Submitted);
+                if let Some(entry) = submitted {
+                    info!("Import batch {number} was already submitted, skipping");
+                    report.skip(batch, entry.id_instruction);
+                    continue;
+                }
+            }
+            let response = match self
+                .fio
+                .validate_dry_run(&batch.import, |i| batch.indices[i])
+            {
+                Ok(()) => self.import(batch.import.clone()).await,
+                Err(e) => Err(e),
+            };
+            match response {
+                Ok(response) => report.record(batch, response),
+                Err(e) => {
+                    warn!("Import batch {number} failed, not sending the rest: {e}");
+                    report.error = Some(e);
+                    break;
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Refuse or warn about an import already in the ledger
+    fn check(&self, fingerprint: &str) -> Result<(), Error> {
+        let Some(entry) = self.ledger.get(fingerprint)? else {
+            return Ok(());
+        };
+        let problem = match entry.state {
+            LedgerState::Failed => return Ok(()),
+            LedgerState::Submitted => format!(
+                "{fingerprint} was submitted as instruction {} at {}",
+                entry.id_instruction.as_deref().unwrap_or("?"),
+                entry.recorded_at
+            ),
+            LedgerState::Pending => format!(
+                "{fingerprint} was sent at {} with unknown outcome",
+                entry.recorded_at
+            ),
+        };
+        match self.resubmission {
+            Resubmission::Refuse => Err(Error::DuplicateImport(problem)),
+            Resubmission::Warn => {
+                warn!("Sending import again: {problem}");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Fingerprint at the start of a truncated journal line, if complete
+fn truncated_fingerprint(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("{\"fingerprint\":\"")?;
+    let (fingerprint, _) = rest.split_once('"')?;
+    (fingerprint.len() == 64 && fingerprint.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then_some(fingerprint)
+}
+
+/// Whether the bank answered the request, so the import was not accepted
+///
+/// A server error (`Error::Malformed`, HTTP 500) may come after the import
+/// was processed, so it is not a refusal.
+fn bank_refused(error: &Error) -> bool {
+    matches!(
+        error.root(),
+        Error::Limit | Error::InvalidToken | Error::InsufficientRights | Error::TooLarge
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::domestic_import;
+
+    fn ledger(name: &str) -> ImportLedger {
+        let path = std::env::temp_dir().join(format!("fiocz-ledger-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        ImportLedger::new(path)
+    }
+
+    #[test]
+    fn fingerprint_follows_contents() {
+        assert_eq!(
+            fingerprint(&domestic_import(1)),
+            fingerprint(&domestic_import(1))
+        );
+        assert_ne!(
+            fingerprint(&domestic_import(1)),
+            fingerprint(&domestic_import(2))
+        );
+        assert_eq!(fingerprint(&domestic_import(1)).len(), 64);
+    }
+
+    #[test]
+    fn journal_keeps_last_state() {
+        let ledger = ledger("journal");
+        let fp = fingerprint(&domestic_import(1));
+        assert!(ledger.get(&fp).unwrap().is_none());
+        ledger.record(&fp, LedgerState::Pending, None, 1).unwrap();
+        ledger
+            .record(&fp, LedgerState::Submitted, Some("42".to_string()), 1)
+            .unwrap();
+        let entry = ledger.get(&fp).unwrap().unwrap();
+        assert_eq!(entry.state, LedgerState::Submitted);
+        assert_eq!(entry.id_instruction.as_deref(), Some("42"));
+        assert_eq!(ledger.entries().unwrap().len(), 1);
+
+        std::fs::write(&ledger.path, "not json\n").unwrap();
+        assert!(matches!(ledger.entries(), Err(Error::CorruptLedger(_))));
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[test]
+    fn truncated_line_tolerated() {
+        let ledger = ledger("truncated");
+        let fp = fingerprint(&domestic_import(1));
+        let other = fingerprint(&domestic_import(2));
+        ledger.record(&fp, LedgerState::Pending, None, 1).unwrap();
+        ledger.record(&other, LedgerState::Failed, None, 1).unwrap();
+        // Crash while recording the outcome of the first import
+        let line =
+            serde_json::to_string(&ledger.record(&fp, LedgerState::Submitted, None, 1).unwrap())
+                .unwrap();
+        let content = std::fs::read_to_string(&ledger.path).unwrap();
+        let cut = content.len() - line.len() / 2 - 1;
+        std::fs::write(&ledger.path, &content[..cut]).unwrap();
+        let entries = ledger.entries().unwrap();
+        assert_eq!(entries[&fp].state, LedgerState::Pending);
+        assert_eq!(entries[&other].state, LedgerState::Failed);
+
+        // Later records start on a new line and the journal stays readable
+        ledger
+            .record(&other, LedgerState::Submitted, None, 1)
+            .unwrap();
+        let entries = ledger.entries().unwrap();
+        assert_eq!(entries[&fp].state, LedgerState::Pending);
+        assert_eq!(entries[&other].state, LedgerState::Submitted);
+
+        // Cut before the fingerprint is complete: the line is just skipped
+        std::fs::write(&ledger.path, "{\"fingerprint\":\"ab").unwrap();
+        assert!(ledger.entries().unwrap().is_empty());
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[test]
+    fn resubmission_policy() {
+        let ledger = ledger("policy");
+        let importer = LedgerImporter::new(Fio::new("token"), ledger.clone(), Resubmission::Refuse);
+        let fp = fingerprint(&domestic_import(1));
+        assert!(importer.check(&fp).is_ok());
+        for (state, allowed) in [
+            (LedgerState::Pending, false),
+            (LedgerState::Submitted, false),
+            (LedgerState::Failed, true),
+        ] {
+            ledger.record(&fp, state, None, 1).unwrap();
+            assert_eq!(importer.check(&fp).is_ok(), allowed, "{state:?}");
+        }
+        ledger.record(&fp, LedgerState::Submitted, None, 1).unwrap();
+        assert!(matches!(
+            importer.check(&fp),
+            Err(Error::DuplicateImport(_))
+        ));
+        let lenient = LedgerImporter::new(Fio::new("token"), ledger.clone(), Resubmission::Warn);
+        assert!(lenient.check(&fp).is_ok());
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batches_resume_after_failure() {
+        use crate::mock::MockServer;
+        use crate::types::transaction::OrderStatus;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = AtomicUsize::new(0);
+        let server = MockServer::start(move |_, _| match calls.fetch_add(1, Ordering::SeqCst) {
+            1 => (409, vec![]),
+            n => (
+                200,
+                format!(
+                    "<responseImport><result><errorCode>0</errorCode>\
+                     <idInstruction>{n}</idInstruction></result></responseImport>"
+                )
+                .into_bytes(),
+            ),
+        })
+        .await;
+        let ledger = ledger("batches");
+        let importer =
+            LedgerImporter::new(server.fio("token"), ledger.clone(), Resubmission::Refuse);
+        let mut orders = domestic_import(1);
+        orders.orders.append(&mut domestic_import(2).orders);
+        let limits = BatchLimits {
+            max_orders: 1,
+            ..BatchLimits::default()
+        };
+
+        let report = importer
+            .import_in_batches(orders.clone(), limits)
+            .await
+            .unwrap();
+        assert_eq!(report.orders[0].status, OrderStatus::Accepted);
+        assert_eq!(report.orders[1].status, OrderStatus::NotSubmitted);
+        assert!(matches!(report.error, Some(Error::Limit)));
+
+        // The accepted batch is not sent again
+        let report = importer.import_in_batches(orders, limits).await.unwrap();
+        assert_eq!(report.orders[0].status, OrderStatus::AlreadySubmitted);
+        assert_eq!(report.orders[0].id_instruction.as_deref(), Some("0"));
+        assert_eq!(report.orders[1].status, OrderStatus::Accepted);
+        assert!(report.is_complete());
+        assert_eq!(server.requests().len(), 3);
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn rerun_on_later_day() {
+        use crate::bulk::CsvImporter;
+        use crate::types::transaction::Type;
+        use chrono::{Days, NaiveDate};
+
+        // Dates defaulted to the day the import is built differ on a rerun
+        let today = domestic_import(1);
+        let mut later = today.clone();
+        let Type::Domestic(order) = &mut later.orders[0] else {
+            unreachable!()
+        };
+        order.date = "2030-01-02".to_string();
+        assert_ne!(fingerprint(&today), fingerprint(&later));
+
+        // A pinned date makes a rerun of the same file a duplicate
+        let csv = "account,amount\n19-2000145399/0800,100\n";
+        let day = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        let read = |date: NaiveDate| {
+            CsvImporter::new("2101179627")
+                .due_date(date)
+                .read(csv.as_bytes())
+                .unwrap()
+                .into_import()
+                .unwrap()
+        };
+        let ledger = ledger("rerun");
+        let first = read(day);
+        ledger
+            .record(&fingerprint(&first), LedgerState::Submitted, None, 1)
+            .unwrap();
+        let importer = LedgerImporter::new(Fio::new("token"), ledger.clone(), Resubmission::Refuse);
+        assert!(matches!(
+            importer.import(read(day)).await,
+            Err(Error::DuplicateImport(_))
+        ));
+        let next = read(day.checked_add_days(Days::new(1)).unwrap());
+        assert_ne!(fingerprint(&first), fingerprint(&next));
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn server_error_leaves_import_pending() {
+        use crate::mock::MockServer;
+
+        let server = MockServer::start(|_, _| (500, vec![])).await;
+        let ledger = ledger("server-error");
+        let importer =
+            LedgerImporter::new(server.fio("token"), ledger.clone(), Resubmission::Refuse);
+        assert!(matches!(
+            importer.import(domestic_import(1)).await,
+            Err(Error::Malformed)
+        ));
+        let fp = fingerprint(&domestic_import(1));
+        assert_eq!(
+            ledger.get(&fp).unwrap().unwrap().state,
+            LedgerState::Pending
+        );
+        assert!(matches!(
+            importer.import(domestic_import(1)).await,
+            Err(Error::DuplicateImport(_))
+        ));
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn duplicate_refused_before_sending() {
+        let ledger = ledger("duplicate");
+        let fp = fingerprint(&domestic_import(1));
+        ledger.record(&fp, LedgerState::Submitted, None, 1).unwrap();
+        let fio = Fio::new("token");
+        let importer = LedgerImporter::new(fio.clone(), ledger.clone(), Resubmission::Refuse);
+        assert!(matches!(
+            importer.import(domestic_import(1)).await,
+            Err(Error::DuplicateImport(_))
+        ));
+        assert!(fio.last_request.lock().await.is_none());
+        std::fs::remove_file(&ledger.path).unwrap();
+    }
+}
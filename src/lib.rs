@@ -39,10 +39,12 @@
 //! | [`Fio::movements_since_last_raw`] | Same, in any export format |
 //! | [`Fio::statements`] | Official statement by year/ID |
 //! | [`Fio::statements_raw`] | Same, in any export format (incl. PDF, MT940) |
+//! | [`Fio::statements_bytes`] | Same, as bytes for binary formats (PDF) |
 //! | [`Fio::last_statement_id`] | Last created statement number |
 //! | [`Fio::set_last_id`] | Set download bookmark by movement ID |
 //! | [`Fio::set_last_date`] | Set download bookmark by date |
 //! | [`Fio::import_transactions`] | Import payments via type-safe builder |
+//! | [`Fio::import_in_batches`] | Import split into batches with per-order results |
 //! | [`Fio::import_raw`] | Import via raw ABO/pain.001/pain.008 payload |
 //! | [`Fio::merchant_transactions_raw`] | POS/gateway card transactions (XML) |
 //!
@@ -53,34 +55,138 @@
 //! interval has passed. The rate limiter is shared across clones of the same
 //! [`Fio`] instance via [`Arc`].
 //!
+//! # Multiple accounts
+//!
+//! [`accounts::FioAccounts`] holds one client per token under a chosen alias
+//! or IBAN and fetches movements for all accounts concurrently.
+//!
+//! # Statement archive
+//!
+//! [`archiver::StatementArchiver`] downloads all official statements up to
+//! [`Fio::last_statement_id`] into a directory tree, skipping the ones
+//! already archived.
+//!
+//! # Synchronisation
+//!
+//! [`sync::SyncEngine`] builds on [`Fio::movements_since_last`] and
+//! [`Fio::set_last_id`] to deliver each movement exactly once, keeping a local
+//! checkpoint that survives crashes. For long-running services,
+//! [`watcher::Watcher`] polls on an interval and broadcasts new movements as
+//! events.
+//!
+//! # Local storage
+//!
+//! [`store::Store`] persists movements, account info and bookmarks for local
+//! querying. Enable the `sqlite` feature for the bundled SQLite backend,
+//! `store::sqlite::SqliteStore`.
+//!
+//! # Bulk import
+//!
+//! [`bulk::CsvImporter`] turns spreadsheet rows into domestic and euro
+//! orders, reporting invalid rows by line number, with a per-currency
+//! preview before [`Fio::import_transactions`].
+//!
+//! # Idempotent import
+//!
+//! [`ledger::LedgerImporter`] records every import in a local journal before
+//! sending it and refuses to send an identical import again when it was
+//! accepted or its outcome is unknown. Its
+//! [`import_in_batches`](ledger::LedgerImporter::import_in_batches) skips
+//! batches already accepted, so an interrupted batch import can be repeated.
+//!
+//! # Approval
+//!
+//! [`approval::ApprovalQueue`] holds proposed imports until enough other
+//! people have approved exactly those orders, then submits them through a
+//! [`ledger::LedgerImporter`].
+//!
+//! # Scheduled payments
+//!
+//! [`schedule::Scheduler`] holds recurring payment templates, such as rent on
+//! the 5th business day of every month, and builds the [`types::transaction::Import`]
+//! due on a date, shifting due dates off weekends and Czech bank holidays.
+//!
+//! # QR payments
+//!
+//! [`qr::spayd::Spayd`] encodes and parses Czech "QR Platba" payloads and
+//! converts them to and from domestic orders; [`qr::epc::Epc`] does the same
+//! for EPC "GiroCode" payloads and euro orders. Enable the `qr` feature to
+//! render them as SVG or PNG images.
+//!
+//! # Token permissions
+//!
+//! Tokens are issued either read-only or with the right to import payments.
+//! Declare it with [`Fio::with_permissions`] or detect it with
+//! [`Fio::probe_permissions`]; import methods on a read-only token then fail
+//! with [`error::Error::InsufficientRights`] before anything is uploaded.
+//!
+//! # Dry run
+//!
+//! [`Fio::with_dry_run`] turns imports and bookmark changes into log
+//! messages with the token redacted, returning a synthetic success, so a
+//! payment pipeline can run in staging against a real token.
+//!
 //! # Error handling
 //!
 //! All methods return `Result<T, error::Error>`. HTTP status codes are mapped to
 //! typed variants:
 //!
-//! - 404 → [`error::Error::Token`] — invalid or deactivated token
+//! - 403 → [`error::Error::InsufficientRights`] — token lacks permission
+//! - 404 → [`error::Error::InvalidToken`] — invalid or deactivated token
 //! - 409 → [`error::Error::Limit`] — rate limit exceeded
 //! - 413 → [`error::Error::TooLarge`] — response too large
 //! - 422 → [`error::Error::HistoricalDataLocked`] — data older than 90 days
 //! - 500 → [`error::Error::Malformed`] — malformed request
 //!
+//! [`accounts::FioAccounts`] wraps the error of a request shared by several
+//! accounts in [`error::Error::Shared`]; [`error::Error::root`] returns the
+//! variant behind it.
+//!
 
+pub mod accounts;
+pub mod approval;
+pub mod archiver;
+pub mod bulk;
 mod client;
 pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod ledger;
+#[cfg(test)]
+mod mock;
+pub mod qr;
+pub mod schedule;
+pub mod store;
+pub mod sync;
 pub mod types;
 mod validation;
+pub mod watcher;
 
-use std::sync::Arc;
+use log::{debug, warn};
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::Instant;
 use tokio::sync::Mutex;
 
-use crate::error::Error;
+use crate::error::{Error, FieldError};
 use crate::types::account_statement::{LastStatementId, Statement};
-use crate::types::transaction::Import;
+use crate::types::import_response::ImportResponse;
+use crate::types::transaction::{BatchImportReport, BatchLimits, Import};
 use crate::types::ExportFormat;
 
 /// Minimum interval between API requests (30 seconds per FIO API docs)
-const MIN_REQUEST_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+pub const MIN_REQUEST_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Permissions of an API token
+///
+/// Fio issues tokens either for reading only or with the additional right to
+/// import payment orders.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TokenPermissions {
+    /// Token can only download data
+    ReadOnly,
+    /// Token can download data and import payment orders
+    ReadWrite,
+}
 
 /// Fiocz API client
 ///
@@ -89,6 +195,9 @@ const MIN_REQUEST_INTERVAL: std::time::Duration = std::time::Duration::from_secs
 pub struct Fio {
     token: String,
     last_request: Arc<Mutex<Option<Instant>>>,
+    permissions: Arc<RwLock<Option<TokenPermissions>>>,
+    dry_run: bool,
+    base_url: String,
 }
 
 impl Fio {
@@ -100,7 +209,125 @@ impl Fio {
         Self {
             token: token.to_string(),
             last_request: Arc::new(Mutex::new(None)),
+            permissions: Arc::new(RwLock::new(None)),
+            dry_run: false,
+            base_url: client::API_URL.to_string(),
+        }
+    }
+
+    /// Send requests to another server, such as a local mock in tests
+    #[cfg(test)]
+    pub(crate) fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Enable or disable dry-run mode
+    ///
+    /// In dry-run mode, imports and bookmark changes are validated and
+    /// serialised as usual, then logged (at `info` level, token redacted)
+    /// instead of being sent. Imports return a successful response with the
+    /// instruction ID `dry-run`. Downloads are not affected.
+    #[must_use]
+    pub const fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Whether mutating calls are only logged, see [`Self::with_dry_run`]
+    #[must_use]
+    pub const fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Declare the permissions of the token
+    ///
+    /// With [`TokenPermissions::ReadOnly`], import methods fail with
+    /// [`Error::InsufficientRights`] without contacting the bank.
+    #[must_use]
+    pub fn with_permissions(self, permissions: TokenPermissions) -> Self {
+        self.set_permissions(permissions);
+        self
+    }
+
+    /// Known permissions of the token, `None` if neither configured nor probed
+    #[must_use]
+    pub fn permissions(&self) -> Option<TokenPermissions> {
+        *self
+            .permissions
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Find out whether the token may import payment orders
+    ///
+    /// Uploads an import file without any orders, which cannot create a
+    /// payment: a read-only token is refused with HTTP 403, while for a
+    /// read-write token the bank reads the file and rejects it as empty. The
+    /// result is remembered for later import calls. Known permissions are
+    /// returned without a request, and in dry-run mode nothing is sent.
+    /// # Returns
+    /// * `Some(TokenPermissions)` - Detected or known permissions
+    /// * `None` - Permissions unknown and not probed in dry-run mode
+    /// # Errors
+    /// * `Error::InvalidToken` - The token does not exist or is deactivated
+    /// * `Error::Limit` - Too many requests
+    /// * `Error::InvalidResponse` - The bank answered neither with 403 nor
+    ///   with an import result
+    pub async fn probe_permissions(&self) -> Result<Option<TokenPermissions>, Error> {
+        if let Some(permissions) = self.permissions() {
+            return Ok(Some(permissions));
+        }
+        let body = Import::new().to_xml();
+        if self.dry_run {
+            self.log_dry_run("POST", "import/?type=xml", Some(&body));
+            return Ok(None);
+        }
+        let (status, text) = self.api_post_response("import/", "xml", body).await?;
+        let permissions = if status == reqwest::StatusCode::FORBIDDEN {
+            TokenPermissions::ReadOnly
+        } else if let Ok(response) = ImportResponse::from_xml(&text) {
+            // The bank looked at the file, so the token may import
+            debug!(
+                "Permission probe answered with error code {}",
+                response.error_code
+            );
+            TokenPermissions::ReadWrite
+        } else {
+            return Err(client::map_status_error(status).unwrap_or_else(|| {
+                Error::InvalidResponse(format!("Unexpected permission probe reply ({status})"))
+            }));
+        };
+        self.set_permissions(permissions);
+        Ok(Some(permissions))
+    }
+
+    fn set_permissions(&self, permissions: TokenPermissions) {
+        *self
+            .permissions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(permissions);
+    }
+
+    /// Fail fast when the token is known to be read-only
+    pub(crate) fn check_import_permission(&self) -> Result<(), Error> {
+        if self.permissions() == Some(TokenPermissions::ReadOnly) {
+            return Err(Error::InsufficientRights);
         }
+        Ok(())
+    }
+
+    async fn api_import(&self, import_type: &str, body: String) -> Result<String, Error> {
+        self.check_import_permission()?;
+        if self.dry_run {
+            self.log_dry_run("POST", &format!("import/?type={import_type}"), Some(&body));
+            return Ok(client::DRY_RUN_IMPORT_RESPONSE.to_string());
+        }
+        let result = self.api_post("import/", import_type, body).await;
+        if matches!(result, Err(Error::InsufficientRights)) {
+            self.set_permissions(TokenPermissions::ReadOnly);
+        }
+        result
     }
 
     /// Get account movements in period (JSON)
@@ -187,46 +414,51 @@ impl Fio {
 
     /// Get account statement (JSON)
     /// # Arguments
-    /// * `year` - Year in format YYYY
-    /// * `id` - Statement ID
+    /// * `statement` - Year and ID of the statement
     /// # Returns
     /// * `Statement` - Account statement
     /// # Errors
-    /// * `Error::InvalidDateFormat` - Invalid date format
-    pub async fn statements(&self, year: &str, id: &str) -> Result<Statement, Error> {
-        if !validation::validate_year_string(year) {
-            return Err(Error::InvalidDateFormat);
-        }
-        self.api_get::<Statement>(&format!(
-            "by-id/{token}/{year}/{id}/transactions.json",
-            token = self.token
-        ))
-        .await
+    /// * `Error::InvalidDateFormat` - Invalid year
+    pub async fn statements(&self, statement: LastStatementId) -> Result<Statement, Error> {
+        self.api_get::<Statement>(&statement_path(&self.token, statement, ExportFormat::Json)?)
+            .await
     }
 
     /// Get account statement in specified format (raw text)
     /// # Arguments
-    /// * `year` - Year in format YYYY
-    /// * `id` - Statement ID
+    /// * `statement` - Year and ID of the statement
     /// * `format` - Export format
     /// # Returns
     /// * `String` - Raw response body in the requested format
     /// # Errors
-    /// * `Error::InvalidDateFormat` - Invalid date format
+    /// * `Error::InvalidDateFormat` - Invalid year
     pub async fn statements_raw(
         &self,
-        year: &str,
-        id: &str,
+        statement: LastStatementId,
         format: ExportFormat,
     ) -> Result<String, Error> {
-        if !validation::validate_year_string(year) {
-            return Err(Error::InvalidDateFormat);
-        }
-        self.api_get_text(&format!(
-            "by-id/{token}/{year}/{id}/transactions.{format}",
-            token = self.token
-        ))
-        .await
+        self.api_get_text(&statement_path(&self.token, statement, format)?)
+            .await
+    }
+
+    /// Get account statement in specified format (raw bytes)
+    ///
+    /// Use this for binary formats such as [`ExportFormat::Pdf`], which cannot
+    /// be represented as a [`String`].
+    /// # Arguments
+    /// * `statement` - Year and ID of the statement
+    /// * `format` - Export format
+    /// # Returns
+    /// * `Vec<u8>` - Raw response body in the requested format
+    /// # Errors
+    /// * `Error::InvalidDateFormat` - Invalid year
+    pub async fn statements_bytes(
+        &self,
+        statement: LastStatementId,
+        format: ExportFormat,
+    ) -> Result<Vec<u8>, Error> {
+        self.api_get_bytes(&statement_path(&self.token, statement, format)?)
+            .await
     }
 
     /// Set last movement id (bookmark)
@@ -269,19 +501,7 @@ impl Fio {
             ))
             .await
         {
-            Ok(id) => id.split_once(',').map_or_else(
-                || {
-                    Err(Error::InvalidResponse(
-                        "Not enough elements returned".to_string(),
-                    ))
-                },
-                |result| {
-                    Ok(LastStatementId {
-                        year: result.0.to_string(),
-                        id: result.1.to_string(),
-                    })
-                },
-            ),
+            Ok(id) => id.parse(),
             Err(e) => Err(e),
         }
     }
@@ -292,9 +512,86 @@ impl Fio {
     /// # Returns
     /// * `String` - Raw XML response from the bank
     /// # Errors
+    /// * `Error::InsufficientRights` - The token is read-only
     /// * `Error::Limit` - Too many requests
+    /// * `Error::InvalidFields` - In dry-run mode, [`Import::validate`] found
+    ///   problems; the messages start with the order index
     pub async fn import_transactions(&self, transactions: Import) -> Result<String, Error> {
-        self.api_post("import/", "xml", transactions.to_xml()).await
+        self.validate_dry_run(&transactions, |index| index)?;
+        self.api_import("xml", transactions.to_xml()).await
+    }
+
+    /// In dry-run mode, fail with the problems [`Import::validate`] finds
+    ///
+    /// `index` maps an order position in `transactions` to the index used in
+    /// the messages, e.g. the position in the import a batch was split from.
+    pub(crate) fn validate_dry_run(
+        &self,
+        transactions: &Import,
+        index: impl Fn(usize) -> usize,
+    ) -> Result<(), Error> {
+        if !self.dry_run {
+            return Ok(());
+        }
+        let problems: Vec<FieldError> = transactions
+            .validate()
+            .into_iter()
+            .map(|d| FieldError {
+                field: d.field,
+                message: format!("order {}: {}", index(d.index), d.message),
+            })
+            .collect();
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidFields(problems))
+        }
+    }
+
+    /// Import transactions in batches the bank accepts
+    ///
+    /// The import is split by [`BatchLimits`] and the batches are submitted
+    /// one after another, each waiting for the rate limit. A batch rejected by
+    /// the bank does not stop the others; a failed request does, and the
+    /// orders of that batch and all later ones are reported as
+    /// [`types::transaction::OrderStatus::NotSubmitted`] with the error in
+    /// [`BatchImportReport::error`]. In dry-run mode, each batch is checked
+    /// with [`Import::validate`] first and problems stop the run with
+    /// `Error::InvalidFields`, the messages starting with the order index.
+    /// # Arguments
+    /// * `transactions` - Transactions to import
+    /// * `limits` - Maximum orders and bytes per batch
+    /// # Returns
+    /// * `BatchImportReport` - Bank responses and the result of each order,
+    ///   in the order of `transactions.orders`
+    /// # Errors
+    /// * `Error::InsufficientRights` - The token is read-only
+    pub async fn import_in_batches(
+        &self,
+        transactions: Import,
+        limits: BatchLimits,
+    ) -> Result<BatchImportReport, Error> {
+        self.check_import_permission()?;
+        let batches = transactions.into_batches(&limits);
+        let mut report = BatchImportReport::new(&batches);
+        for (number, batch) in batches.iter().enumerate() {
+            let response = match self.validate_dry_run(&batch.import, |i| batch.indices[i]) {
+                Ok(()) => match self.api_import("xml", batch.import.to_xml()).await {
+                    Ok(xml) => ImportResponse::from_xml(&xml),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            match response {
+                Ok(response) => report.record(batch, response),
+                Err(e) => {
+                    warn!("Import batch {number} failed, not sending the rest: {e}");
+                    report.error = Some(e);
+                    break;
+                }
+            }
+        }
+        Ok(report)
     }
 
     /// Import transactions using a raw payload in the specified format
@@ -307,9 +604,10 @@ impl Fio {
     /// # Returns
     /// * `String` - Raw XML response from the bank
     /// # Errors
+    /// * `Error::InsufficientRights` - The token is read-only
     /// * `Error::Limit` - Too many requests
     pub async fn import_raw(&self, format: &str, body: String) -> Result<String, Error> {
-        self.api_post("import/", format, body).await
+        self.api_import(format, body).await
     }
 
     /// Get merchant card transactions for a period
@@ -338,3 +636,163 @@ impl Fio {
         .await
     }
 }
+
+fn statement_path(
+    token: &str,
+    statement: LastStatementId,
+    format: ExportFormat,
+) -> Result<String, Error> {
+    let LastStatementId { year, id } = statement;
+    if !validation::validate_year_string(&year.to_string()) {
+        return Err(Error::InvalidDateFormat);
+    }
+    Ok(format!("by-id/{token}/{year}/{id}/transactions.{format}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_only_token_fails_fast() {
+        let fio = Fio::new("token").with_permissions(TokenPermissions::ReadOnly);
+        assert!(matches!(
+            fio.import_transactions(Import::new()).await,
+            Err(Error::InsufficientRights)
+        ));
+        assert!(matches!(
+            fio.import_raw("abo", String::new()).await,
+            Err(Error::InsufficientRights)
+        ));
+        assert!(matches!(
+            fio.import_in_batches(Import::new(), BatchLimits::default())
+                .await,
+            Err(Error::InsufficientRights)
+        ));
+        // Nothing was sent, so the rate limiter was not touched
+        assert!(fio.last_request.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn dry_run_sends_nothing() {
+        let fio = Fio::new("secret-token").with_dry_run(true);
+        assert!(fio.is_dry_run());
+        let order = types::transaction::DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(rust_decimal::Decimal::new(100, 0))
+            .account_to("19-2000145399/0800")
+            .build()
+            .unwrap();
+        let mut import = Import::new();
+        import
+            .orders
+            .push(types::transaction::Type::Domestic(order));
+        let xml = fio.import_transactions(import).await.unwrap();
+        let response = ImportResponse::from_xml(&xml).unwrap();
+        assert!(response.is_ok());
+        assert_eq!(response.id_instruction.as_deref(), Some("dry-run"));
+        fio.set_last_id("123").await.unwrap();
+        fio.set_last_date("2024-01-31").await.unwrap();
+        assert!(matches!(
+            fio.set_last_date("31.1.2024").await,
+            Err(Error::InvalidDateFormat)
+        ));
+        assert!(fio.last_request.lock().await.is_none());
+        assert_eq!(
+            fio.redacted_url("set-last-id/secret-token/123/"),
+            "https://fioapi.fio.cz/v1/rest/set-last-id/<token>/123/"
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_validates_imports() {
+        let fio = Fio::new("token").with_dry_run(true);
+        let mut order = types::transaction::DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(rust_decimal::Decimal::new(100, 0))
+            .account_to("19-2000145399/0800")
+            .build()
+            .unwrap();
+        let valid = types::transaction::Type::Domestic(order.clone());
+        order.date = "2000-01-01".to_string();
+        let mut import = Import::new();
+        import
+            .orders
+            .push(types::transaction::Type::Domestic(order));
+        assert!(matches!(
+            fio.import_transactions(import.clone()).await,
+            Err(Error::InvalidFields(problems)) if problems[0].field == "date"
+        ));
+
+        // Batches are checked too, reporting the index in the whole import
+        import.orders.insert(0, valid);
+        let limits = BatchLimits {
+            max_orders: 1,
+            ..BatchLimits::default()
+        };
+        let report = fio.import_in_batches(import, limits).await.unwrap();
+        assert_eq!(report.responses.len(), 1);
+        assert_eq!(
+            report.orders[1].status,
+            types::transaction::OrderStatus::NotSubmitted
+        );
+        assert!(matches!(
+            report.error,
+            Some(Error::InvalidFields(problems)) if problems[0].message.starts_with("order 1:")
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn probe_reads_import_result() {
+        let server = mock::MockServer::start(|_, _| {
+            let xml = "<responseImport><result><errorCode>11</errorCode>\
+                       <status>error</status></result></responseImport>";
+            (500, xml.as_bytes().to_vec())
+        })
+        .await;
+        let fio = server.fio("token");
+        assert_eq!(
+            fio.probe_permissions().await.unwrap(),
+            Some(TokenPermissions::ReadWrite)
+        );
+        // Known permissions need no further request
+        assert_eq!(
+            fio.probe_permissions().await.unwrap(),
+            Some(TokenPermissions::ReadWrite)
+        );
+        assert_eq!(server.requests(), ["POST /import/"]);
+
+        let forbidden = mock::MockServer::start(|_, _| (403, vec![])).await;
+        let fio = forbidden.fio("token");
+        assert_eq!(
+            fio.probe_permissions().await.unwrap(),
+            Some(TokenPermissions::ReadOnly)
+        );
+        assert!(matches!(
+            fio.import_transactions(Import::new()).await,
+            Err(Error::InsufficientRights)
+        ));
+
+        let missing = mock::MockServer::start(|_, _| (404, vec![])).await;
+        assert!(matches!(
+            missing.fio("token").probe_permissions().await,
+            Err(Error::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn probe_skipped_in_dry_run() {
+        let fio = Fio::new("token").with_dry_run(true);
+        assert_eq!(fio.probe_permissions().await.unwrap(), None);
+        assert!(fio.last_request.lock().await.is_none());
+    }
+
+    #[test]
+    fn permissions_shared_between_clones() {
+        let fio = Fio::new("token");
+        let clone = fio.clone();
+        assert_eq!(clone.permissions(), None);
+        fio.set_permissions(TokenPermissions::ReadWrite);
+        assert_eq!(clone.permissions(), Some(TokenPermissions::ReadWrite));
+    }
+}
@@ -0,0 +1,113 @@
+//! Local HTTP server standing in for the Fio API in tests
+//!
+//! Run such tests with `#[tokio::test(start_paused = true)]` so the 30 s
+//! rate limit between requests passes instantly.
+use std::sync::{Arc, Mutex, PoisonError};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::Fio;
+
+/// Canned response: HTTP status and body
+pub(crate) type Reply = (u16, Vec<u8>);
+
+/// Server answering every request with the reply of a routing closure
+pub(crate) struct MockServer {
+    url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Start a server; `route` gets the request method and the path below
+    /// the API root, such as `/last/token/transactions.json`
+    pub(crate) async fn start<F>(route: F) -> Self
+    where
+        F: Fn(&str, &str) -> Reply + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+        let log = Arc::clone(&requests);
+        let route = Arc::new(route);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let log = Arc::clone(&log);
+                let route = Arc::clone(&route);
+                tokio::spawn(serve(stream, move |method, path| {
+                    log.lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(format!("{method} {path}"));
+                    route(method, path)
+                }));
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// Client for `token` talking to this server
+    pub(crate) fn fio(&self, token: &str) -> Fio {
+        Fio::new(token).with_base_url(&self.url)
+    }
+
+    /// Requests received so far as `METHOD path`
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Read one request, skipping its body, and write the reply
+async fn serve<F>(stream: TcpStream, respond: F)
+where
+    F: FnOnce(&str, &str) -> Reply,
+{
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).await.unwrap();
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut length = 0;
+    let mut chunked = false;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).await.unwrap();
+        let header = header.trim_end().to_ascii_lowercase();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("content-length:") {
+            length = value.trim().parse().unwrap();
+        } else if header.starts_with("transfer-encoding:") && header.contains("chunked") {
+            chunked = true;
+        }
+    }
+    if chunked {
+        loop {
+            let mut size = String::new();
+            reader.read_line(&mut size).await.unwrap();
+            let size = usize::from_str_radix(size.trim(), 16).unwrap();
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).await.unwrap();
+            if size == 0 {
+                break;
+            }
+        }
+    } else {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+    }
+
+    let (status, body) = respond(&method, &path);
+    let head = format!(
+        "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    let mut stream = reader.into_inner();
+    stream.write_all(head.as_bytes()).await.unwrap();
+    stream.write_all(&body).await.unwrap();
+    stream.shutdown().await.unwrap();
+}
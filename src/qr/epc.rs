@@ -0,0 +1,446 @@
+//! EPC069-12 SEPA credit transfer payload ("GiroCode")
+//!
+//! ```text
+//! BCD
+//! 002
+//! 1
+//! SCT
+//! GIBACZPX
+//! Example s.r.o.
+//! CZ6508000000192000145399
+//! EUR480.50
+//!
+//!
+//! Invoice 42
+//! ```
+//!
+//! Each line holds one field; trailing empty lines are omitted. The payload
+//! is always written in UTF-8 (character set `1`).
+use rust_decimal::Decimal;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, FieldError};
+use crate::types::bic::Bic;
+use crate::types::currency::Currency;
+use crate::types::iban::Iban;
+use crate::types::transaction::T2Transaction;
+
+/// Maximum payload size in bytes
+const MAX_PAYLOAD_LEN: usize = 331;
+
+/// Maximum length of the beneficiary name
+const MAX_NAME_LEN: usize = 70;
+
+/// Maximum length of the structured creditor reference
+const MAX_REFERENCE_LEN: usize = 35;
+
+/// Maximum length of the unstructured remittance text
+const MAX_TEXT_LEN: usize = 140;
+
+/// Maximum length of the beneficiary to originator information
+const MAX_INFORMATION_LEN: usize = 70;
+
+/// Length of a remittance info line of a [`T2Transaction`]
+const REMITTANCE_LINE_LEN: usize = 35;
+
+/// Payload version
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum EpcVersion {
+    /// `001`, BIC mandatory
+    V1,
+    /// `002`, BIC optional within the EEA
+    #[default]
+    V2,
+}
+
+impl EpcVersion {
+    const fn code(self) -> &'static str {
+        match self {
+            Self::V1 => "001",
+            Self::V2 => "002",
+        }
+    }
+}
+
+/// EPC QR payload describing a SEPA credit transfer in EUR
+///
+/// ```
+/// use fiocz_rs::qr::epc::Epc;
+/// use rust_decimal::Decimal;
+///
+/// let mut epc = Epc::new("CZ6508000000192000145399".parse().unwrap(), "Example s.r.o.");
+/// epc.amount = Some(Decimal::new(48050, 2));
+/// epc.text = Some("Invoice 42".to_string());
+/// let payload = epc.to_payload().unwrap();
+/// assert_eq!(payload.parse::<Epc>().unwrap(), epc);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Epc {
+    /// Payload version
+    pub version: EpcVersion,
+    /// Beneficiary bank, mandatory for [`EpcVersion::V1`]
+    pub bic: Option<Bic>,
+    /// Beneficiary name, up to 70 characters
+    pub name: String,
+    /// Beneficiary account
+    pub iban: Iban,
+    /// Amount in EUR, 0.01 to 999999999.99
+    pub amount: Option<Decimal>,
+    /// Purpose code, four letters (e.g. `GDDS`)
+    pub purpose: Option<String>,
+    /// Structured creditor reference (ISO 11649), up to 35 characters
+    pub reference: Option<String>,
+    /// Unstructured remittance text, up to 140 characters
+    pub text: Option<String>,
+    /// Beneficiary to originator information, up to 70 characters
+    pub information: Option<String>,
+}
+
+impl Epc {
+    /// Create a version 002 payload for a beneficiary
+    #[must_use]
+    pub fn new(iban: Iban, name: &str) -> Self {
+        Self {
+            version: EpcVersion::V2,
+            bic: None,
+            name: name.to_string(),
+            iban,
+            amount: None,
+            purpose: None,
+            reference: None,
+            text: None,
+            information: None,
+        }
+    }
+
+    /// Check the field limits of the EPC069-12 guidelines
+    /// # Errors
+    /// * `Error::InvalidFields` - All fields exceeding their limits
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut errors = Vec::new();
+        let mut push = |field: &'static str, message: String| {
+            errors.push(FieldError { field, message });
+        };
+        if self.version == EpcVersion::V1 && self.bic.is_none() {
+            push("bic", "is mandatory in version 001".to_string());
+        }
+        if self.name.trim().is_empty() {
+            push("name", "is mandatory".to_string());
+        }
+        for (field, value, max_len) in [
+            ("name", Some(&self.name), MAX_NAME_LEN),
+            ("reference", self.reference.as_ref(), MAX_REFERENCE_LEN),
+            ("text", self.text.as_ref(), MAX_TEXT_LEN),
+            (
+                "information",
+                self.information.as_ref(),
+                MAX_INFORMATION_LEN,
+            ),
+        ] {
+            if let Some(value) = value {
+                if value.chars().count() > max_len {
+                    push(field, format!("is longer than {max_len} characters"));
+                }
+                if value.contains(['\n', '\r']) {
+                    push(field, "must be a single line".to_string());
+                }
+            }
+        }
+        if let Some(amount) = self.amount {
+            if amount < Decimal::new(1, 2) || amount > Decimal::new(99_999_999_999, 2) {
+                push(
+                    "amount",
+                    format!("{amount} is not between 0.01 and 999999999.99"),
+                );
+            } else if amount.normalize().scale() > 2 {
+                push("amount", format!("{amount} has more than 2 decimal places"));
+            }
+        }
+        if let Some(purpose) = &self.purpose {
+            if purpose.len() != 4 || !purpose.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                push(
+                    "purpose",
+                    format!("{purpose:?} is not a four character code"),
+                );
+            }
+        }
+        if self.reference.is_some() && self.text.is_some() {
+            push(
+                "text",
+                "cannot be combined with a structured reference".to_string(),
+            );
+        }
+        if self.to_string().len() > MAX_PAYLOAD_LEN {
+            push("payload", format!("is longer than {MAX_PAYLOAD_LEN} bytes"));
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidFields(errors))
+        }
+    }
+
+    /// Validated payload text
+    /// # Errors
+    /// * `Error::InvalidFields` - A field exceeds its limits, see [`Self::validate`]
+    pub fn to_payload(&self) -> Result<String, Error> {
+        self.validate()?;
+        Ok(self.to_string())
+    }
+
+    /// Payload for paying a euro order
+    ///
+    /// The remittance info lines are joined into the unstructured text.
+    /// # Errors
+    /// * `Error::InvalidEpc` - The order is not in EUR
+    /// * `Error::InvalidFields` - The order does not fit the EPC limits
+    pub fn from_t2(transaction: &T2Transaction) -> Result<Self, Error> {
+        if transaction.currency != Currency::Eur {
+            return Err(Error::InvalidEpc(format!(
+                "order in {} cannot be paid in EUR",
+                transaction.currency
+            )));
+        }
+        let mut epc = Self::new(transaction.account_to.clone(), &transaction.benef_name);
+        epc.bic.clone_from(&transaction.bic);
+        epc.amount = Some(transaction.amount);
+        let text = [
+            &transaction.remittance_info1,
+            &transaction.remittance_info2,
+            &transaction.remittance_info3,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+        epc.text = Some(text).filter(|t| !t.is_empty());
+        epc.validate()?;
+        Ok(epc)
+    }
+
+    /// Euro order paying this payload from a Fio account
+    ///
+    /// The structured reference or the text is split into the three 35
+    /// character remittance info lines. The order is due today.
+    /// # Arguments
+    /// * `account_from` - Paying Fio account (`prefix-number`)
+    /// # Errors
+    /// * `Error::InvalidEpc` - The payload has no amount, or a text longer than
+    ///   the 105 characters of the remittance info lines
+    /// * `Error::InvalidFields` - The resulting order is invalid, e.g. the name
+    ///   or remittance text is too long for a euro order
+    pub fn to_t2(&self, account_from: &str) -> Result<T2Transaction, Error> {
+        let amount = self
+            .amount
+            .ok_or_else(|| Error::InvalidEpc("amount is missing".to_string()))?;
+        let mut builder = T2Transaction::builder()
+            .account_from(account_from)
+            .amount(amount)
+            .account_to(self.iban.electronic())
+            .benef_name(&self.name);
+        if let Some(bic) = &self.bic {
+            builder = builder.bic(&bic.to_string());
+        }
+        let remittance: Vec<char> = self
+            .reference
+            .as_ref()
+            .or(self.text.as_ref())
+            .map(|r| r.chars().collect())
+            .unwrap_or_default();
+        let lines: Vec<String> = remittance
+            .chunks(REMITTANCE_LINE_LEN)
+            .map(|line| line.iter().collect())
+            .collect();
+        if lines.len() > 3 {
+            return Err(Error::InvalidEpc(format!(
+                "remittance text is {} characters long, at most {} fit a euro order",
+                remittance.len(),
+                3 * REMITTANCE_LINE_LEN
+            )));
+        }
+        if let Some(line) = lines.first() {
+            builder = builder.remittance_info1(line);
+        }
+        if let Some(line) = lines.get(1) {
+            builder = builder.remittance_info2(line);
+        }
+        if let Some(line) = lines.get(2) {
+            builder = builder.remittance_info3(line);
+        }
+        builder.build()
+    }
+}
+
+impl fmt::Display for Epc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        let mut lines = vec![
+            "BCD".to_string(),
+            self.version.code().to_string(),
+            "1".to_string(),
+            "SCT".to_string(),
+            self.bic
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            self.name.clone(),
+            self.iban.to_string(),
+            self.amount
+                .map(|a| format!("{}{}", Currency::Eur, a.round_dp(2)))
+                .unwrap_or_default(),
+            optional(&self.purpose),
+            optional(&self.reference),
+            optional(&self.text),
+            optional(&self.information),
+        ];
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl FromStr for Epc {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().map(|l| l.trim_end_matches('\r')).collect();
+        let line = |i: usize| lines.get(i).copied().unwrap_or_default().trim();
+        let optional = |i: usize| Some(line(i).to_string()).filter(|v| !v.is_empty());
+        if line(0) != "BCD" {
+            return Err(invalid("missing BCD service tag"));
+        }
+        let version = match line(1) {
+            "001" => EpcVersion::V1,
+            "002" => EpcVersion::V2,
+            other => return Err(invalid(&format!("unsupported version {other:?}"))),
+        };
+        if !matches!(line(2), "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8") {
+            return Err(invalid(&format!("unknown character set {:?}", line(2))));
+        }
+        if line(3) != "SCT" {
+            return Err(invalid(&format!(
+                "unsupported identification {:?}",
+                line(3)
+            )));
+        }
+        if lines.len() > 12 {
+            return Err(invalid("too many lines"));
+        }
+        let amount = match line(7) {
+            "" => None,
+            amount => Some(
+                amount
+                    .strip_prefix("EUR")
+                    .and_then(|a| a.parse::<Decimal>().ok())
+                    .ok_or_else(|| invalid(&format!("{amount:?} is not a EUR amount")))?,
+            ),
+        };
+        let epc = Self {
+            version,
+            bic: optional(4).map(|bic| bic.parse()).transpose()?,
+            name: line(5).to_string(),
+            iban: line(6).parse()?,
+            amount,
+            purpose: optional(8),
+            reference: optional(9),
+            text: optional(10),
+            information: optional(11),
+        };
+        epc.validate()?;
+        Ok(epc)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidEpc(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Epc {
+        let mut epc = Epc::new("DE89370400440532013000".parse().unwrap(), "Muster GmbH");
+        epc.bic = Some("COBADEFFXXX".parse().unwrap());
+        epc.amount = Some(Decimal::new(12345, 2));
+        epc.purpose = Some("GDDS".to_string());
+        epc.text = Some("Invoice 2024-17".to_string());
+        epc
+    }
+
+    #[test]
+    fn encode() {
+        assert_eq!(
+            sample().to_payload().unwrap(),
+            "BCD\n002\n1\nSCT\nCOBADEFFXXX\nMuster GmbH\nDE89370400440532013000\n\
+             EUR123.45\nGDDS\n\nInvoice 2024-17"
+        );
+        let minimal = Epc::new("DE89370400440532013000".parse().unwrap(), "Muster GmbH");
+        assert_eq!(
+            minimal.to_string(),
+            "BCD\n002\n1\nSCT\n\nMuster GmbH\nDE89370400440532013000"
+        );
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let payload = sample().to_payload().unwrap().replace('\n', "\r\n");
+        assert_eq!(payload.parse::<Epc>().unwrap(), sample());
+        for input in [
+            "BCD\n003\n1\nSCT\n\nName\nDE89370400440532013000",
+            "BCD\n002\n1\nINST\n\nName\nDE89370400440532013000",
+            "BCD\n002\n1\nSCT\n\nName\nDE89370400440532013000\nCZK10",
+            "BCD\n001\n1\nSCT\n\nName\nDE89370400440532013000",
+            "XYZ\n002\n1\nSCT\n\nName\nDE89370400440532013000",
+        ] {
+            assert!(input.parse::<Epc>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn length_limits() {
+        let mut epc = sample();
+        epc.name = "N".repeat(71);
+        epc.reference = Some("RF18539007547034".to_string());
+        epc.amount = Some(Decimal::new(1, 3));
+        epc.purpose = Some("GOODS".to_string());
+        let Err(Error::InvalidFields(errors)) = epc.validate() else {
+            panic!("expected field errors");
+        };
+        let fields: Vec<_> = errors.iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["name", "amount", "purpose", "text"]);
+    }
+
+    #[test]
+    fn t2_conversion() {
+        let mut epc = sample();
+        epc.text = Some("A".repeat(80));
+        let order = epc.to_t2("2101179627").unwrap();
+        assert_eq!(order.benef_name, "Muster GmbH");
+        assert_eq!(order.account_to, epc.iban);
+        assert_eq!(order.amount, Decimal::new(12345, 2));
+        assert_eq!(order.remittance_info1.as_deref(), Some(&*"A".repeat(35)));
+        assert_eq!(order.remittance_info3.as_deref(), Some(&*"A".repeat(10)));
+
+        let back = Epc::from_t2(&order).unwrap();
+        assert_eq!(back.bic, epc.bic);
+        assert_eq!(back.text.unwrap().len(), 82);
+
+        let mut czk = order.clone();
+        czk.currency = Currency::Czk;
+        assert!(matches!(Epc::from_t2(&czk), Err(Error::InvalidEpc(_))));
+
+        epc.text = Some("B".repeat(105));
+        let order = epc.to_t2("2101179627").unwrap();
+        assert_eq!(order.remittance_info3.as_deref(), Some(&*"B".repeat(35)));
+        epc.text = Some("B".repeat(140));
+        assert!(matches!(epc.to_t2("2101179627"), Err(Error::InvalidEpc(_))));
+
+        epc.text = None;
+        epc.amount = None;
+        assert!(matches!(epc.to_t2("2101179627"), Err(Error::InvalidEpc(_))));
+    }
+}
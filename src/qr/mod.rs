@@ -0,0 +1,59 @@
+//! QR payment payloads
+//!
+//! Payloads are plain strings; with the `qr` feature they can also be
+//! rendered as QR code images.
+pub mod epc;
+pub mod spayd;
+
+#[cfg(feature = "qr")]
+use crate::error::Error;
+
+/// Render a payload as an SVG QR code
+/// # Arguments
+/// * `payload` - Text to encode, e.g. a [`spayd::Spayd`] string
+/// * `size` - Minimal width and height in pixels
+/// # Errors
+/// * `Error::QrCode` - The payload does not fit into a QR code
+#[cfg(feature = "qr")]
+pub fn render_svg(payload: &str, size: u32) -> Result<String, Error> {
+    use qrcode::render::svg;
+
+    let code = qrcode::QrCode::with_error_correction_level(payload, qrcode::EcLevel::M)?;
+    Ok(code
+        .render::<svg::Color<'_>>()
+        .min_dimensions(size, size)
+        .build())
+}
+
+/// Render a payload as a PNG QR code
+/// # Arguments
+/// * `payload` - Text to encode, e.g. a [`spayd::Spayd`] string
+/// * `size` - Minimal width and height in pixels
+/// # Errors
+/// * `Error::QrCode` - The payload does not fit into a QR code
+/// * `Error::Image` - The image cannot be encoded
+#[cfg(feature = "qr")]
+pub fn render_png(payload: &str, size: u32) -> Result<Vec<u8>, Error> {
+    let code = qrcode::QrCode::with_error_correction_level(payload, qrcode::EcLevel::M)?;
+    let image = code
+        .render::<image::Luma<u8>>()
+        .min_dimensions(size, size)
+        .build();
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+#[cfg(all(test, feature = "qr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_images() {
+        let payload = "SPD*1.0*ACC:CZ6508000000192000145399*AM:480.50";
+        let svg = render_svg(payload, 200).unwrap();
+        assert!(svg.contains("<svg"));
+        let png = render_png(payload, 200).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+}
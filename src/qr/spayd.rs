@@ -0,0 +1,463 @@
+//! Short Payment Descriptor (SPAYD), the payload of Czech "QR Platba" codes
+//!
+//! ```text
+//! SPD*1.0*ACC:CZ6508000000192000145399+GIBACZPX*AM:480.50*CC:CZK*MSG:INVOICE 42*X-VS:42
+//! ```
+//!
+//! Encoding writes the keys in alphabetical order, which is the canonical
+//! form the optional `CRC32` checksum is computed over.
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::account_number::CzAccountNumber;
+use crate::types::bic::Bic;
+use crate::types::currency::Currency;
+use crate::types::iban::Iban;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+use crate::types::transaction::DomesticTransaction;
+
+/// Header and version written by [`Spayd`]
+const HEADER: &str = "SPD*1.0";
+
+/// Maximum length of the message for the recipient
+const MAX_MESSAGE_LEN: usize = 60;
+
+/// Maximum length of the recipient name
+const MAX_NAME_LEN: usize = 35;
+
+/// Keys with a dedicated field; everything else is kept in [`Spayd::extra`]
+const KNOWN_KEYS: &[&str] = &[
+    "ACC", "AM", "CC", "CRC32", "DT", "MSG", "RN", "X-KS", "X-SS", "X-VS",
+];
+
+/// Short Payment Descriptor
+///
+/// ```
+/// use fiocz_rs::qr::spayd::Spayd;
+/// use rust_decimal::Decimal;
+///
+/// let mut spayd = Spayd::new("CZ6508000000192000145399".parse().unwrap());
+/// spayd.amount = Some(Decimal::new(48050, 2));
+/// spayd.vs = Some("42".parse().unwrap());
+/// let payload = spayd.to_string();
+/// assert_eq!(payload, "SPD*1.0*ACC:CZ6508000000192000145399*AM:480.50*X-VS:42");
+/// assert_eq!(payload.parse::<Spayd>().unwrap(), spayd);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Spayd {
+    /// Recipient account (`ACC`)
+    pub account: Iban,
+    /// Recipient bank, written after the IBAN in `ACC`
+    pub bic: Option<Bic>,
+    /// Amount (`AM`)
+    pub amount: Option<Decimal>,
+    /// Currency (`CC`), CZK if absent
+    pub currency: Option<Currency>,
+    /// Variable symbol (`X-VS`)
+    pub vs: Option<VariableSymbol>,
+    /// Specific symbol (`X-SS`)
+    pub ss: Option<SpecificSymbol>,
+    /// Constant symbol (`X-KS`)
+    pub ks: Option<ConstantSymbol>,
+    /// Message for the recipient (`MSG`), up to 60 characters
+    pub message: Option<String>,
+    /// Due date (`DT`)
+    pub due_date: Option<NaiveDate>,
+    /// Recipient name (`RN`), up to 35 characters
+    pub recipient_name: Option<String>,
+    /// Other keys, kept verbatim (decoded values); keys with a dedicated
+    /// field are not written
+    pub extra: BTreeMap<String, String>,
+}
+
+impl Spayd {
+    /// Create a payment descriptor for an account
+    #[must_use]
+    pub fn new(account: Iban) -> Self {
+        Self {
+            account,
+            bic: None,
+            amount: None,
+            currency: None,
+            vs: None,
+            ss: None,
+            ks: None,
+            message: None,
+            due_date: None,
+            recipient_name: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Canonical payload followed by its `CRC32` checksum
+    #[must_use]
+    pub fn to_string_with_crc32(&self) -> String {
+        let payload = self.to_string();
+        format!(
+            "{payload}*CRC32:{:08X}",
+            crc32fast::hash(payload.as_bytes())
+        )
+    }
+
+    /// Descriptor for paying a domestic order
+    ///
+    /// The message for the recipient is cut to the first 60 characters, the
+    /// most `MSG` holds; a domestic order allows 140.
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - `account_to` or `bank_code` is invalid
+    pub fn from_domestic(transaction: &DomesticTransaction) -> Result<Self, Error> {
+        let account =
+            CzAccountNumber::parse_with_bank(&transaction.account_to, &transaction.bank_code)?;
+        let mut spayd = Self::new(account.to_iban());
+        spayd.bic = account.bic();
+        spayd.amount = Some(transaction.amount);
+        spayd.currency = Some(transaction.currency);
+        spayd.vs.clone_from(&transaction.vs);
+        spayd.ss.clone_from(&transaction.ss);
+        spayd.ks.clone_from(&transaction.ks);
+        spayd.message = transaction
+            .message_for_recipient
+            .as_ref()
+            .map(|m| m.chars().take(MAX_MESSAGE_LEN).collect());
+        spayd.due_date = NaiveDate::parse_from_str(&transaction.date, "%Y-%m-%d").ok();
+        Ok(spayd)
+    }
+
+    /// Domestic order paying this descriptor from a Fio account
+    ///
+    /// The due date is used when present, otherwise the order is due today.
+    /// # Arguments
+    /// * `account_from` - Paying Fio account (`prefix-number`)
+    /// # Errors
+    /// * `Error::InvalidSpayd` - The descriptor has no amount, a non-Czech IBAN or a foreign currency
+    /// * `Error::InvalidFields` - The resulting order is invalid
+    pub fn to_domestic(&self, account_from: &str) -> Result<DomesticTransaction, Error> {
+        let amount = self
+            .amount
+            .ok_or_else(|| Error::InvalidSpayd("amount is missing".to_string()))?;
+        let currency = self.currency.unwrap_or(Currency::Czk);
+        if currency != Currency::Czk {
+            return Err(Error::InvalidSpayd(format!(
+                "{currency} cannot be paid as a domestic payment"
+            )));
+        }
+        let account = self
+            .account
+            .to_cz_account()
+            .map_err(|e| Error::InvalidSpayd(e.to_string()))?;
+        let mut builder = DomesticTransaction::builder()
+            .account_from(account_from)
+            .amount(amount)
+            .account_to(&account.to_string());
+        if let Some(vs) = &self.vs {
+            builder = builder.vs(vs.as_str());
+        }
+        if let Some(ss) = &self.ss {
+            builder = builder.ss(ss.as_str());
+        }
+        if let Some(ks) = &self.ks {
+            builder = builder.ks(ks.as_str());
+        }
+        if let Some(message) = &self.message {
+            builder = builder.message_for_recipient(message);
+        }
+        if let Some(date) = self.due_date {
+            builder = builder.date(&date.to_string());
+        }
+        builder.build()
+    }
+}
+
+impl fmt::Display for Spayd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields: BTreeMap<&str, String> = BTreeMap::new();
+        let account = match &self.bic {
+            Some(bic) => format!("{}+{bic}", self.account),
+            None => self.account.to_string(),
+        };
+        fields.insert("ACC", account);
+        if let Some(amount) = self.amount {
+            let mut amount = amount.round_dp(2);
+            amount.rescale(2);
+            fields.insert("AM", amount.to_string());
+        }
+        if let Some(currency) = self.currency {
+            fields.insert("CC", currency.to_string());
+        }
+        if let Some(date) = self.due_date {
+            fields.insert("DT", date.format("%Y%m%d").to_string());
+        }
+        if let Some(message) = &self.message {
+            fields.insert("MSG", escape(message));
+        }
+        if let Some(name) = &self.recipient_name {
+            fields.insert("RN", escape(name));
+        }
+        if let Some(ks) = &self.ks {
+            fields.insert("X-KS", ks.to_string());
+        }
+        if let Some(ss) = &self.ss {
+            fields.insert("X-SS", ss.to_string());
+        }
+        if let Some(vs) = &self.vs {
+            fields.insert("X-VS", vs.to_string());
+        }
+        // The dedicated fields win, an extra "AM" must not change the amount
+        for (key, value) in &self.extra {
+            if !KNOWN_KEYS.contains(&key.as_str()) {
+                fields.insert(key, escape(value));
+            }
+        }
+        f.write_str(HEADER)?;
+        for (key, value) in fields {
+            write!(f, "*{key}:{value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Spayd {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('*');
+        if parts.next() != Some("SPD") {
+            return Err(invalid("missing SPD header"));
+        }
+        let version = match parts.next() {
+            Some(version) if version.starts_with("1.") => version,
+            _ => return Err(invalid("unsupported version")),
+        };
+        let mut raw: BTreeMap<&str, &str> = BTreeMap::new();
+        for part in parts.filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once(':')
+                .ok_or_else(|| invalid(&format!("{part} is not a key:value pair")))?;
+            if raw.insert(key, value).is_some() {
+                return Err(invalid(&format!("duplicate key {key}")));
+            }
+        }
+        if let Some(expected) = raw.get("CRC32") {
+            let canonical = raw
+                .iter()
+                .filter(|(key, _)| **key != "CRC32")
+                .fold(format!("SPD*{version}"), |acc, (key, value)| {
+                    format!("{acc}*{key}:{value}")
+                });
+            let actual = format!("{:08X}", crc32fast::hash(canonical.as_bytes()));
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(invalid("CRC32 checksum does not match"));
+            }
+        }
+
+        let acc = raw.get("ACC").ok_or_else(|| invalid("ACC is missing"))?;
+        let (account, bic) = match acc.split_once('+') {
+            Some((iban, bic)) => (iban, Some(bic.parse()?)),
+            None => (*acc, None),
+        };
+        let mut spayd = Self::new(account.parse()?);
+        spayd.bic = bic;
+        if let Some(amount) = raw.get("AM") {
+            let amount: Decimal = amount
+                .parse()
+                .map_err(|_| invalid(&format!("{amount} is not an amount")))?;
+            if amount.scale() > 2 {
+                return Err(invalid(&format!("{amount} has more than 2 decimal places")));
+            }
+            spayd.amount = Some(amount);
+        }
+        spayd.currency = raw.get("CC").map(|cc| cc.parse()).transpose()?;
+        spayd.vs = raw.get("X-VS").map(|vs| vs.parse()).transpose()?;
+        spayd.ss = raw.get("X-SS").map(|ss| ss.parse()).transpose()?;
+        spayd.ks = raw.get("X-KS").map(|ks| ks.parse()).transpose()?;
+        if let Some(date) = raw.get("DT") {
+            spayd.due_date = Some(
+                NaiveDate::parse_from_str(date, "%Y%m%d")
+                    .map_err(|_| invalid(&format!("{date} is not a YYYYMMDD date")))?,
+            );
+        }
+        spayd.message = raw
+            .get("MSG")
+            .map(|m| limited("MSG", unescape(m)?, MAX_MESSAGE_LEN))
+            .transpose()?;
+        spayd.recipient_name = raw
+            .get("RN")
+            .map(|n| limited("RN", unescape(n)?, MAX_NAME_LEN))
+            .transpose()?;
+        for (key, value) in raw {
+            if !KNOWN_KEYS.contains(&key) {
+                spayd.extra.insert(key.to_string(), unescape(value)?);
+            }
+        }
+        Ok(spayd)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidSpayd(message.to_string())
+}
+
+fn limited(key: &str, value: String, max_len: usize) -> Result<String, Error> {
+    if value.chars().count() > max_len {
+        return Err(invalid(&format!(
+            "{key} is longer than {max_len} characters"
+        )));
+    }
+    Ok(value)
+}
+
+/// Percent-encode the characters with a meaning in SPAYD
+fn escape(value: &str) -> String {
+    value.replace('%', "%25").replace('*', "%2A")
+}
+
+fn unescape(value: &str) -> Result<String, Error> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut input = value.bytes();
+    while let Some(b) = input.next() {
+        if b == b'%' {
+            let hex: Vec<u8> = input.by_ref().take(2).collect();
+            let decoded = std::str::from_utf8(&hex)
+                .ok()
+                .filter(|hex| hex.len() == 2)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| invalid(&format!("invalid escape in {value}")))?;
+            bytes.push(decoded);
+        } else {
+            bytes.push(b);
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid(&format!("{value} is not UTF-8")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Spayd {
+        let mut spayd = Spayd::new("CZ6508000000192000145399".parse().unwrap());
+        spayd.bic = Some("GIBACZPX".parse().unwrap());
+        spayd.amount = Some(Decimal::new(4805, 1));
+        spayd.currency = Some(Currency::Czk);
+        spayd.vs = Some("1234567890".parse().unwrap());
+        spayd.ks = Some("308".parse().unwrap());
+        spayd.message = Some("Faktura *42* 100%".to_string());
+        spayd.due_date = NaiveDate::from_ymd_opt(2030, 1, 31);
+        spayd.recipient_name = Some("Jan Novák".to_string());
+        spayd
+    }
+
+    #[test]
+    fn encode_canonical() {
+        assert_eq!(
+            sample().to_string(),
+            "SPD*1.0*ACC:CZ6508000000192000145399+GIBACZPX*AM:480.50*CC:CZK*DT:20300131\
+             *MSG:Faktura %2A42%2A 100%25*RN:Jan Novák*X-KS:308*X-VS:1234567890"
+        );
+    }
+
+    #[test]
+    fn round_trip_with_crc() {
+        let payload = sample().to_string_with_crc32();
+        assert!(payload.contains("*CRC32:"));
+        assert_eq!(payload.parse::<Spayd>().unwrap(), sample());
+        let tampered = payload.replace("AM:480.50", "AM:980.50");
+        assert!(matches!(
+            tampered.parse::<Spayd>(),
+            Err(Error::InvalidSpayd(_))
+        ));
+
+        // The checksum covers the version the payload was issued with
+        let v2 = "SPD*1.2*ACC:CZ6508000000192000145399*AM:100";
+        let signed = format!("{v2}*CRC32:{:08X}", crc32fast::hash(v2.as_bytes()));
+        assert_eq!(
+            signed.parse::<Spayd>().unwrap().amount,
+            Some(Decimal::new(100, 0))
+        );
+        assert!(signed.replace("1.2", "1.0").parse::<Spayd>().is_err());
+    }
+
+    #[test]
+    fn parse_any_key_order_and_extras() {
+        let spayd: Spayd =
+            "SPD*1.0*X-VS:0042*AM:100*ACC:CZ6508000000192000145399*NT:P*X-URL:https%3A%2F%2Fx.cz"
+                .parse()
+                .unwrap();
+        assert_eq!(spayd.vs.unwrap().as_str(), "42");
+        assert_eq!(spayd.amount, Some(Decimal::new(100, 0)));
+        assert_eq!(spayd.extra["NT"], "P");
+        assert_eq!(spayd.extra["X-URL"], "https://x.cz");
+    }
+
+    #[test]
+    fn extra_cannot_override_fields() {
+        let mut spayd = sample();
+        spayd.extra.insert("AM".to_string(), "99999.00".to_string());
+        spayd
+            .extra
+            .insert("ACC".to_string(), "CZ0000000000000000000000".to_string());
+        spayd.extra.insert("NT".to_string(), "P".to_string());
+        assert_eq!(spayd.to_string(), {
+            let mut expected = sample();
+            expected.extra.insert("NT".to_string(), "P".to_string());
+            expected.to_string()
+        });
+        assert!(spayd.to_string().contains("*AM:480.50*"));
+        assert!(!spayd.to_string().contains("99999"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for input in [
+            "SPX*1.0*ACC:CZ6508000000192000145399",
+            "SPD*2.0*ACC:CZ6508000000192000145399",
+            "SPD*1.0*AM:100",
+            "SPD*1.0*ACC:CZ6508000000192000145398",
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:1.001",
+            "SPD*1.0*ACC:CZ6508000000192000145399*DT:2030-01-31",
+            "SPD*1.0*ACC:CZ6508000000192000145399*AM:1*AM:2",
+        ] {
+            assert!(input.parse::<Spayd>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn domestic_conversion() {
+        let mut spayd = sample();
+        spayd.message = Some("Faktura 42 €".to_string());
+        assert!(matches!(
+            spayd.to_domestic("2101179627"),
+            Err(Error::InvalidFields(_))
+        ));
+        let order = sample().to_domestic("2101179627").unwrap();
+        assert_eq!(
+            order.message_for_recipient.as_deref(),
+            Some("Faktura *42* 100%")
+        );
+        assert_eq!(order.account_to, "19-2000145399");
+        assert_eq!(order.bank_code, "0800");
+        assert_eq!(order.amount, Decimal::new(4805, 1));
+        assert_eq!(order.date, "2030-01-31");
+        assert_eq!(order.ks, Some("0308".parse().unwrap()));
+
+        let back = Spayd::from_domestic(&order).unwrap();
+        assert_eq!(back.account, sample().account);
+        assert_eq!(back.bic, sample().bic);
+        assert_eq!(back.vs, sample().vs);
+        let mut long = order.clone();
+        long.message_for_recipient = Some("x".repeat(140));
+        let cut = Spayd::from_domestic(&long).unwrap().message.unwrap();
+        assert_eq!(cut, "x".repeat(60));
+
+        let mut euro = sample();
+        euro.currency = Some(Currency::Eur);
+        assert!(matches!(
+            euro.to_domestic("2101179627"),
+            Err(Error::InvalidSpayd(_))
+        ));
+    }
+}
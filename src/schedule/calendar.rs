@@ -0,0 +1,124 @@
+//! Czech bank business days
+//!
+//! Banks do not settle payments on weekends and public holidays, including
+//! Good Friday (since 2016) and Easter Monday.
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+/// Fixed-date public holidays as (month, day)
+const FIXED_HOLIDAYS: [(u32, u32); 11] = [
+    (1, 1),   // Restoration of the Czech state
+    (5, 1),   // Labour Day
+    (5, 8),   // Liberation Day
+    (7, 5),   // Saints Cyril and Methodius
+    (7, 6),   // Jan Hus Day
+    (9, 28),  // Czech Statehood Day
+    (10, 28), // Independent Czechoslovak State Day
+    (11, 17), // Struggle for Freedom and Democracy Day
+    (12, 24), // Christmas Eve
+    (12, 25), // Christmas Day
+    (12, 26), // St. Stephen's Day
+];
+
+/// Easter Sunday of a year (Gregorian calendar)
+#[must_use]
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    #[allow(clippy::cast_sign_loss)]
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).expect("Easter is in March or April")
+}
+
+/// Whether a date is a Czech public holiday
+#[must_use]
+pub fn is_holiday(date: NaiveDate) -> bool {
+    if FIXED_HOLIDAYS.contains(&(date.month(), date.day())) {
+        return true;
+    }
+    let easter = easter_sunday(date.year());
+    let good_friday = easter - Days::new(2);
+    let easter_monday = easter + Days::new(1);
+    date == easter_monday || (date == good_friday && date.year() >= 2016)
+}
+
+/// Whether banks settle payments on a date
+#[must_use]
+pub fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !is_holiday(date)
+}
+
+/// The date itself if it is a business day, otherwise the next one
+#[must_use]
+pub fn next_business_day(date: NaiveDate) -> NaiveDate {
+    date.iter_days()
+        .find(|d| is_business_day(*d))
+        .expect("business days never run out")
+}
+
+/// The date itself if it is a business day, otherwise the previous one
+#[must_use]
+pub fn previous_business_day(date: NaiveDate) -> NaiveDate {
+    let mut date = date;
+    while !is_business_day(date) {
+        date = date.pred_opt().expect("business days never run out");
+    }
+    date
+}
+
+/// Business days of a month in order
+pub fn business_days(year: i32, month: u32) -> impl Iterator<Item = NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .into_iter()
+        .flat_map(|first| first.iter_days())
+        .take_while(move |d| d.month() == month)
+        .filter(|d| is_business_day(*d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn easter() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2019), date(2019, 4, 21));
+        assert_eq!(easter_sunday(2038), date(2038, 4, 25));
+    }
+
+    #[test]
+    fn holidays() {
+        assert!(is_holiday(date(2024, 3, 29)));
+        assert!(is_holiday(date(2024, 4, 1)));
+        assert!(!is_holiday(date(2015, 4, 3)));
+        assert!(is_holiday(date(2015, 4, 6)));
+        assert!(is_holiday(date(2024, 11, 17)));
+        assert!(!is_holiday(date(2024, 11, 18)));
+    }
+
+    #[test]
+    fn business_day_shifting() {
+        // Saturday and Czech Statehood Day
+        assert!(!is_business_day(date(2024, 9, 28)));
+        assert_eq!(next_business_day(date(2024, 9, 28)), date(2024, 9, 30));
+        assert_eq!(previous_business_day(date(2024, 9, 28)), date(2024, 9, 27));
+        assert_eq!(next_business_day(date(2024, 12, 24)), date(2024, 12, 27));
+        assert_eq!(next_business_day(date(2024, 12, 27)), date(2024, 12, 27));
+        assert_eq!(business_days(2024, 1).count(), 22);
+    }
+}
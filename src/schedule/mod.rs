@@ -0,0 +1,352 @@
+//! Recurring payment templates
+//!
+//! A [`Template`] is an order with a [`Recurrence`] rule. [`Scheduler`]
+//! collects templates and materialises the orders due on a date into an
+//! [`Import`], with the order date set to that date. Rules resolve to bank
+//! business days (see [`calendar`]), shifting dates off weekends and Czech
+//! public holidays.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use fiocz_rs::schedule::{Recurrence, Scheduler, Template};
+//! use fiocz_rs::types::transaction::{DomesticTransaction, Type};
+//! use rust_decimal::Decimal;
+//!
+//! let rent = DomesticTransaction::builder()
+//!     .account_from("2101179627")
+//!     .amount(Decimal::new(25000, 0))
+//!     .account_to("19-2000145399/0800")
+//!     .build()
+//!     .unwrap();
+//! let mut scheduler = Scheduler::new();
+//! scheduler.add(Template::new("rent", Type::Domestic(rent), Recurrence::NthBusinessDay(5)));
+//!
+//! // 1 January 2024 is a holiday, so the 5th business day is 8 January
+//! let day = NaiveDate::from_ymd_opt(2024, 1, 8).unwrap();
+//! assert_eq!(scheduler.import_for(day).orders.len(), 1);
+//! ```
+pub mod calendar;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+use crate::types::transaction::{Import, Type};
+
+/// Direction to move a date that is not a business day
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Shift {
+    /// Next business day
+    #[default]
+    Following,
+    /// Previous business day
+    Preceding,
+}
+
+impl Shift {
+    fn apply(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Following => calendar::next_business_day(date),
+            Self::Preceding => calendar::previous_business_day(date),
+        }
+    }
+}
+
+/// When a template is due
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Recurrence {
+    /// Day of every month, the last day in shorter months
+    MonthlyOnDay {
+        /// Day of the month, 1 to 31
+        day: u32,
+        /// Shift when the day is not a business day
+        shift: Shift,
+    },
+    /// N-th business day of every month, starting at 1; skipped in months
+    /// with fewer business days
+    NthBusinessDay(u32),
+    /// Last business day of every month
+    LastBusinessDay,
+    /// Day of every week
+    Weekly {
+        /// Day of the week
+        weekday: Weekday,
+        /// Shift when the day is not a business day
+        shift: Shift,
+    },
+}
+
+impl Recurrence {
+    /// Due dates between `from` and `to`, both inclusive, in order
+    #[must_use]
+    pub fn due_dates(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        // Shifting moves a date by at most a few days, so nominal dates just
+        // outside the range may land inside it
+        let start = from.checked_sub_days(Days::new(7)).unwrap_or(from);
+        let end = to.checked_add_days(Days::new(7)).unwrap_or(to);
+        let mut dates: Vec<NaiveDate> = match *self {
+            Self::Weekly { weekday, shift } => start
+                .iter_days()
+                .take_while(|d| *d <= end)
+                .filter(|d| d.weekday() == weekday)
+                .map(|d| shift.apply(d))
+                .collect(),
+            _ => months(start, end)
+                .filter_map(|(year, month)| self.in_month(year, month))
+                .collect(),
+        };
+        dates.retain(|d| (from..=to).contains(d));
+        dates.dedup();
+        dates
+    }
+
+    /// Whether the rule is due on a date
+    #[must_use]
+    pub fn is_due(&self, date: NaiveDate) -> bool {
+        !self.due_dates(date, date).is_empty()
+    }
+
+    /// Due date of a monthly rule in a month
+    fn in_month(&self, year: i32, month: u32) -> Option<NaiveDate> {
+        match *self {
+            Self::MonthlyOnDay { day, shift } => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                let nominal = first.with_day(day.clamp(1, last.day()))?;
+                Some(shift.apply(nominal))
+            }
+            Self::NthBusinessDay(n) => {
+                calendar::business_days(year, month).nth(usize::try_from(n).ok()?.checked_sub(1)?)
+            }
+            Self::LastBusinessDay => calendar::business_days(year, month).last(),
+            Self::Weekly { .. } => None,
+        }
+    }
+}
+
+/// (year, month) of every month touched by a date range
+fn months(from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = (i32, u32)> {
+    let first = from.with_day(1);
+    std::iter::successors(first, |d| d.checked_add_months(Months::new(1)))
+        .take_while(move |d| *d <= to)
+        .map(|d| (d.year(), d.month()))
+}
+
+/// Order repeated according to a rule
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Template {
+    /// Name for logs and reports
+    pub name: String,
+    /// Order to repeat; its date is replaced by the due date
+    pub order: Type,
+    /// When the order is due
+    pub recurrence: Recurrence,
+    /// First day the template may be due
+    pub start: Option<NaiveDate>,
+    /// Last day the template may be due
+    pub end: Option<NaiveDate>,
+}
+
+impl Template {
+    /// Create new template without start or end
+    #[must_use]
+    pub fn new(name: &str, order: Type, recurrence: Recurrence) -> Self {
+        Self {
+            name: name.to_string(),
+            order,
+            recurrence,
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Set the first day the template may be due
+    #[must_use]
+    pub const fn starting(mut self, start: NaiveDate) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Set the last day the template may be due
+    #[must_use]
+    pub const fn until(mut self, end: NaiveDate) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Due dates between `from` and `to`, both inclusive, within the
+    /// template's start and end
+    #[must_use]
+    pub fn due_dates(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let from = self.start.map_or(from, |start| from.max(start));
+        let to = self.end.map_or(to, |end| to.min(end));
+        if from > to {
+            return vec![];
+        }
+        self.recurrence.due_dates(from, to)
+    }
+
+    /// Whether the template is due on a date
+    #[must_use]
+    pub fn is_due(&self, date: NaiveDate) -> bool {
+        !self.due_dates(date, date).is_empty()
+    }
+
+    /// The order with its date set
+    #[must_use]
+    pub fn order_for(&self, date: NaiveDate) -> Type {
+        let mut order = self.order.clone();
+        let date = date.to_string();
+        match &mut order {
+            Type::Domestic(t) => t.date = date,
+            Type::Euro(t) => t.date = date,
+            Type::Foreign(t) => t.date = date,
+        }
+        order
+    }
+}
+
+/// Collection of templates producing the orders due on a date
+#[derive(Debug, Clone, Default)]
+pub struct Scheduler {
+    templates: Vec<Template>,
+}
+
+impl Scheduler {
+    /// Create new empty scheduler
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { templates: vec![] }
+    }
+
+    /// Add a template
+    pub fn add(&mut self, template: Template) -> &mut Self {
+        self.templates.push(template);
+        self
+    }
+
+    /// All templates
+    #[must_use]
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// Templates due on a date
+    #[must_use]
+    pub fn due_on(&self, date: NaiveDate) -> Vec<&Template> {
+        self.templates.iter().filter(|t| t.is_due(date)).collect()
+    }
+
+    /// Import with the orders of all templates due on a date
+    #[must_use]
+    pub fn import_for(&self, date: NaiveDate) -> Import {
+        Import {
+            orders: self
+                .due_on(date)
+                .into_iter()
+                .map(|t| t.order_for(date))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{domestic, euro};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn monthly_on_day() {
+        let following = Recurrence::MonthlyOnDay {
+            day: 28,
+            shift: Shift::Following,
+        };
+        let preceding = Recurrence::MonthlyOnDay {
+            day: 28,
+            shift: Shift::Preceding,
+        };
+        let september = (date(2024, 9, 1), date(2024, 9, 30));
+        assert_eq!(
+            following.due_dates(september.0, september.1),
+            [date(2024, 9, 30)]
+        );
+        assert_eq!(
+            preceding.due_dates(september.0, september.1),
+            [date(2024, 9, 27)]
+        );
+
+        let end_of_month = Recurrence::MonthlyOnDay {
+            day: 31,
+            shift: Shift::Preceding,
+        };
+        assert_eq!(
+            end_of_month.due_dates(date(2024, 2, 1), date(2024, 4, 30)),
+            [date(2024, 2, 29), date(2024, 3, 28), date(2024, 4, 30)]
+        );
+        // 31 August 2024 is a Saturday, shifted into September
+        let shifted = Recurrence::MonthlyOnDay {
+            day: 31,
+            shift: Shift::Following,
+        };
+        assert_eq!(
+            shifted.due_dates(date(2024, 9, 1), date(2024, 9, 30)),
+            [date(2024, 9, 2), date(2024, 9, 30)]
+        );
+    }
+
+    #[test]
+    fn business_day_rules() {
+        let fifth = Recurrence::NthBusinessDay(5);
+        assert!(fifth.is_due(date(2024, 1, 8)));
+        assert!(!fifth.is_due(date(2024, 1, 5)));
+        assert!(Recurrence::NthBusinessDay(30)
+            .due_dates(date(2024, 1, 1), date(2024, 12, 31))
+            .is_empty());
+        assert!(Recurrence::NthBusinessDay(0)
+            .due_dates(date(2024, 1, 1), date(2024, 12, 31))
+            .is_empty());
+        // 29 March 2024 is Good Friday
+        assert!(Recurrence::LastBusinessDay.is_due(date(2024, 3, 28)));
+
+        let weekly = Recurrence::Weekly {
+            weekday: Weekday::Mon,
+            shift: Shift::Following,
+        };
+        // Easter Monday moves to Tuesday
+        assert_eq!(
+            weekly.due_dates(date(2024, 3, 25), date(2024, 4, 8)),
+            [date(2024, 3, 25), date(2024, 4, 2), date(2024, 4, 8)]
+        );
+    }
+
+    #[test]
+    fn scheduler_materialises_orders() {
+        let euro = Type::Euro(euro(100));
+        let mut scheduler = Scheduler::new();
+        scheduler
+            .add(Template::new(
+                "rent",
+                Type::Domestic(domestic(1000)),
+                Recurrence::NthBusinessDay(5),
+            ))
+            .add(
+                Template::new("hosting", euro, Recurrence::LastBusinessDay)
+                    .starting(date(2024, 2, 1))
+                    .until(date(2024, 12, 31)),
+            );
+        assert_eq!(scheduler.templates().len(), 2);
+
+        let import = scheduler.import_for(date(2024, 1, 8));
+        assert_eq!(import.orders.len(), 1);
+        let Type::Domestic(order) = &import.orders[0] else {
+            panic!("expected domestic order");
+        };
+        assert_eq!(order.date, "2024-01-08");
+
+        assert!(scheduler.import_for(date(2024, 1, 31)).orders.is_empty());
+        assert_eq!(scheduler.due_on(date(2024, 2, 29))[0].name, "hosting");
+        assert!(scheduler.due_on(date(2025, 1, 31)).is_empty());
+    }
+}
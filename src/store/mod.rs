@@ -0,0 +1,360 @@
+//! Local persistence of downloaded data
+//!
+//! The [`Store`] trait describes a backend that keeps movements, account
+//! [`Info`] snapshots and download bookmarks so they can be queried locally.
+//! [`MemoryStore`] is always available; the SQLite backend
+//! `sqlite::SqliteStore` is enabled by the `sqlite` cargo feature.
+
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, PoisonError};
+
+use crate::error::Error;
+use crate::types::account_statement::{Info, Statement};
+use crate::types::movement::Movement;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+
+/// Storage backend for downloaded movements
+pub trait Store: Send + Sync {
+    /// Insert movements, replacing stored movements with the same ID
+    /// # Arguments
+    /// * `account_id` - Account the movements belong to
+    /// * `movements` - Movements to store
+    /// # Returns
+    /// * `usize` - Number of movements written
+    /// # Errors
+    /// * Backend specific storage error
+    fn upsert_movements(&self, account_id: &str, movements: &[Movement]) -> Result<usize, Error>;
+
+    /// Store a snapshot of account info, replacing one for the same period
+    /// # Errors
+    /// * Backend specific storage error
+    fn save_info(&self, info: &Info) -> Result<(), Error>;
+
+    /// Latest stored account info snapshot (by end of period)
+    /// # Errors
+    /// * Backend specific storage error
+    fn latest_info(&self, account_id: &str) -> Result<Option<Info>, Error>;
+
+    /// Record the last downloaded movement ID for an account
+    /// # Errors
+    /// * Backend specific storage error
+    fn record_bookmark(&self, account_id: &str, id: i64) -> Result<(), Error>;
+
+    /// Last recorded movement ID for an account
+    /// # Errors
+    /// * Backend specific storage error
+    fn bookmark(&self, account_id: &str) -> Result<Option<i64>, Error>;
+
+    /// Find stored movements matching the query, ordered by movement ID
+    /// # Errors
+    /// * Backend specific storage error
+    fn query(&self, query: &MovementQuery) -> Result<Vec<Movement>, Error>;
+
+    /// Store account info and all movements of a statement
+    /// # Returns
+    /// * `usize` - Number of movements written
+    /// # Errors
+    /// * `Error::InvalidResponse` - A movement could not be parsed
+    /// * Backend specific storage error
+    fn save_statement(&self, statement: &Statement) -> Result<usize, Error> {
+        let info = &statement.account_statement.info;
+        self.save_info(info)?;
+        self.upsert_movements(&info.account_id, &statement.movements()?)
+    }
+}
+
+/// Filter for [`Store::query`]
+///
+/// All set criteria must match. Dates are compared on the `YYYY-MM-DD` part
+/// of the movement date and are inclusive.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MovementQuery {
+    /// Account ID
+    pub account_id: Option<String>,
+    /// First day (inclusive), `YYYY-MM-DD`
+    pub date_from: Option<String>,
+    /// Last day (inclusive), `YYYY-MM-DD`
+    pub date_to: Option<String>,
+    /// Minimum amount (inclusive)
+    pub min_amount: Option<Decimal>,
+    /// Maximum amount (inclusive)
+    pub max_amount: Option<Decimal>,
+    /// Variable symbol
+    pub vs: Option<VariableSymbol>,
+    /// Constant symbol
+    pub ks: Option<ConstantSymbol>,
+    /// Specific symbol
+    pub ss: Option<SpecificSymbol>,
+}
+
+impl MovementQuery {
+    /// Create new query matching everything
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to an account
+    #[must_use]
+    pub fn account(mut self, account_id: &str) -> Self {
+        self.account_id = Some(account_id.to_string());
+        self
+    }
+
+    /// Restrict to a date range (inclusive, `YYYY-MM-DD`)
+    #[must_use]
+    pub fn between(mut self, from: &str, to: &str) -> Self {
+        self.date_from = Some(from.to_string());
+        self.date_to = Some(to.to_string());
+        self
+    }
+
+    /// Restrict to an amount range (inclusive)
+    #[must_use]
+    pub const fn amount(mut self, min: Decimal, max: Decimal) -> Self {
+        self.min_amount = Some(min);
+        self.max_amount = Some(max);
+        self
+    }
+
+    /// Restrict to a variable symbol
+    #[must_use]
+    pub fn vs(mut self, vs: VariableSymbol) -> Self {
+        self.vs = Some(vs);
+        self
+    }
+
+    /// Restrict to a constant symbol
+    #[must_use]
+    pub fn ks(mut self, ks: ConstantSymbol) -> Self {
+        self.ks = Some(ks);
+        self
+    }
+
+    /// Restrict to a specific symbol
+    #[must_use]
+    pub fn ss(mut self, ss: SpecificSymbol) -> Self {
+        self.ss = Some(ss);
+        self
+    }
+
+    /// Check a movement against all criteria except the account
+    #[must_use]
+    pub fn matches(&self, movement: &Movement) -> bool {
+        let day = movement.day();
+        self.date_from.as_deref().is_none_or(|from| day >= from)
+            && self.date_to.as_deref().is_none_or(|to| day <= to)
+            && self.min_amount.is_none_or(|min| movement.amount >= min)
+            && self.max_amount.is_none_or(|max| movement.amount <= max)
+            && symbol_matches(self.vs.as_ref(), movement.vs.as_ref())
+            && symbol_matches(self.ks.as_ref(), movement.ks.as_ref())
+            && symbol_matches(self.ss.as_ref(), movement.ss.as_ref())
+    }
+}
+
+fn symbol_matches<T: PartialEq>(wanted: Option<&T>, actual: Option<&T>) -> bool {
+    wanted.is_none_or(|wanted| actual == Some(wanted))
+}
+
+/// In-memory [`Store`], mainly useful for tests
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    inner: Mutex<MemoryInner>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryInner {
+    movements: BTreeMap<i64, (String, Movement)>,
+    infos: HashMap<String, Vec<Info>>,
+    bookmarks: HashMap<String, i64>,
+}
+
+impl MemoryStore {
+    /// Create new empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Store for MemoryStore {
+    fn upsert_movements(&self, account_id: &str, movements: &[Movement]) -> Result<usize, Error> {
+        let mut inner = self.lock();
+        for movement in movements {
+            inner
+                .movements
+                .insert(movement.id, (account_id.to_string(), movement.clone()));
+        }
+        Ok(movements.len())
+    }
+
+    fn save_info(&self, info: &Info) -> Result<(), Error> {
+        let mut inner = self.lock();
+        let infos = inner.infos.entry(info.account_id.clone()).or_default();
+        infos.retain(|i| i.date_start != info.date_start || i.date_end != info.date_end);
+        infos.push(info.clone());
+        Ok(())
+    }
+
+    fn latest_info(&self, account_id: &str) -> Result<Option<Info>, Error> {
+        Ok(self.lock().infos.get(account_id).and_then(|infos| {
+            infos
+                .iter()
+                .max_by(|a, b| a.date_end.cmp(&b.date_end))
+                .cloned()
+        }))
+    }
+
+    fn record_bookmark(&self, account_id: &str, id: i64) -> Result<(), Error> {
+        self.lock().bookmarks.insert(account_id.to_string(), id);
+        Ok(())
+    }
+
+    fn bookmark(&self, account_id: &str) -> Result<Option<i64>, Error> {
+        Ok(self.lock().bookmarks.get(account_id).copied())
+    }
+
+    fn query(&self, query: &MovementQuery) -> Result<Vec<Movement>, Error> {
+        Ok(self
+            .lock()
+            .movements
+            .values()
+            .filter(|(account, _)| query.account_id.as_ref().is_none_or(|a| a == account))
+            .filter(|(_, movement)| query.matches(movement))
+            .map(|(_, movement)| movement.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::types::currency::Currency;
+
+    pub(crate) fn movement(id: i64, date: &str, amount: i64, vs: Option<&str>) -> Movement {
+        Movement {
+            id,
+            date: format!("{date}+0100"),
+            amount: Decimal::new(amount, 0),
+            currency: Currency::Czk,
+            counter_account: None,
+            counter_account_name: None,
+            bank_code: None,
+            bank_name: None,
+            ks: None,
+            vs: vs.map(|vs| vs.parse().unwrap()),
+            ss: None,
+            user_identification: None,
+            message_for_recipient: None,
+            movement_type: None,
+            executed_by: None,
+            specification: None,
+            comment: None,
+            bic: None,
+            instruction_id: None,
+            payer_reference: None,
+            payment_reason: None,
+        }
+    }
+
+    pub(crate) fn info(account_id: &str, date_end: &str) -> Info {
+        Info {
+            account_id: account_id.to_string(),
+            bank_id: "2010".to_string(),
+            currency: Currency::Czk,
+            iban: "CZ5020100000002101179627".parse().ok(),
+            bic: "FIOBCZPPXXX".parse().ok(),
+            opening_balance: Decimal::new(100, 0),
+            closing_balance: Decimal::new(200, 0),
+            date_start: "2024-01-01+0100".to_string(),
+            date_end: date_end.to_string(),
+            year_list: serde_json::Value::Null,
+            id_list: None,
+            id_from: None,
+            id_to: None,
+            id_last_download: None,
+        }
+    }
+
+    /// Behaviour every [`Store`] implementation must satisfy
+    pub(crate) fn exercise(store: &dyn Store) {
+        let written = store
+            .upsert_movements(
+                "1",
+                &[
+                    movement(1, "2024-01-10", 100, Some("42")),
+                    movement(2, "2024-01-20", -50, None),
+                    movement(3, "2024-02-01", 300, Some("42")),
+                ],
+            )
+            .unwrap();
+        assert_eq!(written, 3);
+        store
+            .upsert_movements("2", &[movement(4, "2024-01-15", 10, None)])
+            .unwrap();
+        // Re-inserting replaces instead of duplicating
+        store
+            .upsert_movements("1", &[movement(2, "2024-01-20", -60, None)])
+            .unwrap();
+
+        let all = store.query(&MovementQuery::new().account("1")).unwrap();
+        assert_eq!(all.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(all[1].amount, Decimal::new(-60, 0));
+
+        let by_vs = store
+            .query(&MovementQuery::new().vs("0042".parse().unwrap()))
+            .unwrap();
+        assert_eq!(by_vs.len(), 2);
+
+        let january = store
+            .query(&MovementQuery::new().between("2024-01-01", "2024-01-31"))
+            .unwrap();
+        assert_eq!(
+            january.iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+
+        let positive = store
+            .query(&MovementQuery::new().amount(Decimal::new(50, 0), Decimal::new(1000, 0)))
+            .unwrap();
+        assert_eq!(
+            positive.iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        assert_eq!(store.bookmark("1").unwrap(), None);
+        store.record_bookmark("1", 3).unwrap();
+        store.record_bookmark("1", 5).unwrap();
+        assert_eq!(store.bookmark("1").unwrap(), Some(5));
+
+        assert_eq!(store.latest_info("1").unwrap(), None);
+        store.save_info(&info("1", "2024-01-31+0100")).unwrap();
+        store.save_info(&info("1", "2024-02-29+0100")).unwrap();
+        store.save_info(&info("1", "2024-01-31+0100")).unwrap();
+        assert_eq!(
+            store.latest_info("1").unwrap().unwrap().date_end,
+            "2024-02-29+0100"
+        );
+    }
+
+    #[test]
+    fn memory_store() {
+        exercise(&MemoryStore::new());
+    }
+
+    #[test]
+    fn query_matches_symbols() {
+        let query = MovementQuery::new().vs("42".parse().unwrap());
+        assert!(query.matches(&movement(1, "2024-01-01", 1, Some("42"))));
+        assert!(!query.matches(&movement(1, "2024-01-01", 1, Some("43"))));
+        assert!(!query.matches(&movement(1, "2024-01-01", 1, None)));
+    }
+}
@@ -0,0 +1,235 @@
+//! SQLite implementation of [`Store`]
+//!
+//! Movements and account info snapshots are kept as JSON alongside the
+//! indexed columns used for querying. The schema is versioned with
+//! `PRAGMA user_version` and migrated when the store is opened.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+
+use super::{MovementQuery, Store};
+use crate::error::Error;
+use crate::types::account_statement::Info;
+use crate::types::movement::Movement;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+
+/// Schema migrations, applied in order; index + 1 is the schema version
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE movements (
+        id INTEGER PRIMARY KEY,
+        account_id TEXT NOT NULL,
+        day TEXT NOT NULL,
+        vs TEXT,
+        ks TEXT,
+        ss TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX movements_account_day ON movements (account_id, day);
+    CREATE INDEX movements_vs ON movements (vs);
+    CREATE TABLE infos (
+        account_id TEXT NOT NULL,
+        date_start TEXT NOT NULL,
+        date_end TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (account_id, date_start, date_end)
+    );
+    CREATE TABLE bookmarks (
+        account_id TEXT PRIMARY KEY,
+        movement_id INTEGER NOT NULL
+    );",
+    // Symbols are stored without leading zeros since typed symbols
+    "UPDATE movements SET
+        vs = CASE WHEN ltrim(vs, '0') = '' THEN '0' ELSE ltrim(vs, '0') END,
+        ks = CASE WHEN ltrim(ks, '0') = '' THEN '0' ELSE ltrim(ks, '0') END,
+        ss = CASE WHEN ltrim(ss, '0') = '' THEN '0' ELSE ltrim(ss, '0') END;",
+];
+
+/// SQLite backed [`Store`]
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    /// Open (or create) a database file and migrate it to the current schema
+    /// # Arguments
+    /// * `path` - Path of the database file
+    /// # Errors
+    /// * `Error::Sqlite` - The database could not be opened or migrated
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a fresh in-memory database
+    /// # Errors
+    /// * `Error::Sqlite` - The database could not be created
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut connection: Connection) -> Result<Self, Error> {
+        migrate(&mut connection)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    /// Current schema version of the database
+    /// # Errors
+    /// * `Error::Sqlite` - The version could not be read
+    pub fn schema_version(&self) -> Result<usize, Error> {
+        Ok(schema_version(&self.lock())?)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn schema_version(connection: &Connection) -> rusqlite::Result<usize> {
+    connection.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+fn migrate(connection: &mut Connection) -> Result<(), Error> {
+    let current = schema_version(connection)?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = connection.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+impl Store for SqliteStore {
+    fn upsert_movements(&self, account_id: &str, movements: &[Movement]) -> Result<usize, Error> {
+        let mut connection = self.lock();
+        let tx = connection.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO movements (id, account_id, day, vs, ks, ss, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for movement in movements {
+                stmt.execute(params![
+                    movement.id,
+                    account_id,
+                    movement.day(),
+                    movement.vs.as_ref().map(VariableSymbol::as_str),
+                    movement.ks.as_ref().map(ConstantSymbol::as_str),
+                    movement.ss.as_ref().map(SpecificSymbol::as_str),
+                    serde_json::to_string(movement)?,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(movements.len())
+    }
+
+    fn save_info(&self, info: &Info) -> Result<(), Error> {
+        self.lock().execute(
+            "INSERT OR REPLACE INTO infos (account_id, date_start, date_end, data)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                info.account_id,
+                info.date_start,
+                info.date_end,
+                serde_json::to_string(info)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn latest_info(&self, account_id: &str) -> Result<Option<Info>, Error> {
+        let data: Option<String> = self
+            .lock()
+            .query_row(
+                "SELECT data FROM infos WHERE account_id = ?1 ORDER BY date_end DESC LIMIT 1",
+                params![account_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+    }
+
+    fn record_bookmark(&self, account_id: &str, id: i64) -> Result<(), Error> {
+        self.lock().execute(
+            "INSERT OR REPLACE INTO bookmarks (account_id, movement_id) VALUES (?1, ?2)",
+            params![account_id, id],
+        )?;
+        Ok(())
+    }
+
+    fn bookmark(&self, account_id: &str) -> Result<Option<i64>, Error> {
+        Ok(self
+            .lock()
+            .query_row(
+                "SELECT movement_id FROM bookmarks WHERE account_id = ?1",
+                params![account_id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn query(&self, query: &MovementQuery) -> Result<Vec<Movement>, Error> {
+        // Dates and symbols are filtered in SQL; amounts are compared exactly
+        // on the decoded `Decimal` afterwards.
+        let connection = self.lock();
+        let mut stmt = connection.prepare_cached(
+            "SELECT data FROM movements
+             WHERE (?1 IS NULL OR account_id = ?1)
+               AND (?2 IS NULL OR day >= ?2)
+               AND (?3 IS NULL OR day <= ?3)
+               AND (?4 IS NULL OR vs = ?4)
+               AND (?5 IS NULL OR ks = ?5)
+               AND (?6 IS NULL OR ss = ?6)
+             ORDER BY id",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                query.account_id,
+                query.date_from,
+                query.date_to,
+                query.vs.as_ref().map(VariableSymbol::as_str),
+                query.ks.as_ref().map(ConstantSymbol::as_str),
+                query.ss.as_ref().map(SpecificSymbol::as_str),
+            ],
+            |row| row.get::<_, String>(0),
+        )?;
+        let mut result = Vec::new();
+        for data in rows {
+            let movement: Movement = serde_json::from_str(&data?)?;
+            if query.matches(&movement) {
+                result.push(movement);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqlite_store() {
+        super::super::tests::exercise(&SqliteStore::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let path = std::env::temp_dir().join(format!("fiocz-store-{}.db", std::process::id()));
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+            store.record_bookmark("1", 7).unwrap();
+        }
+        let store = SqliteStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+        assert_eq!(store.bookmark("1").unwrap(), Some(7));
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
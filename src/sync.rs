@@ -0,0 +1,371 @@
+//! Persistent synchronisation on top of the `last/{token}` bookmark
+//!
+//! [`Fio::movements_since_last`] advances the server-side bookmark as soon as
+//! the response is sent, so a process that crashes before persisting the
+//! movements loses them. [`SyncEngine`] keeps its own checkpoint (the ID of the
+//! last movement handed to the user callback) in a [`CheckpointStore`]. Each
+//! statement reports where the bookmark stood before the download; when that
+//! is ahead of the checkpoint, the engine rewinds the bookmark with
+//! [`Fio::set_last_id`] and pulls again.
+//!
+//! ```no_run
+//! use fiocz_rs::sync::{FileCheckpointStore, SyncEngine};
+//! use fiocz_rs::Fio;
+//!
+//! # async fn run() -> Result<(), fiocz_rs::error::Error> {
+//! let engine = SyncEngine::new(
+//!     Fio::new("your-api-token"),
+//!     FileCheckpointStore::new("fio.checkpoint"),
+//! );
+//! let report = engine
+//!     .run(|movement| {
+//!         println!("{} {}", movement.id, movement.amount);
+//!         Ok(())
+//!     })
+//!     .await?;
+//! println!("Delivered {} movements", report.delivered);
+//! # Ok(())
+//! # }
+//! ```
+
+use log::{debug, info, warn};
+use std::path::PathBuf;
+
+use crate::error::Error;
+use crate::types::movement::Movement;
+use crate::Fio;
+
+/// Storage for the ID of the last processed movement
+pub trait CheckpointStore: Send + Sync {
+    /// Load the last processed movement ID, `None` if nothing was processed yet
+    /// # Errors
+    /// * `Error::Io` - The store could not be read
+    /// * `Error::CorruptCheckpoint` - The stored value is not a movement ID
+    fn load(&self) -> Result<Option<i64>, Error>;
+
+    /// Persist the last processed movement ID
+    /// # Errors
+    /// * `Error::Io` - The store could not be written
+    fn save(&self, id: i64) -> Result<(), Error>;
+}
+
+/// In-memory checkpoint store, mainly useful for tests
+#[derive(Debug, Default)]
+pub struct MemoryCheckpointStore {
+    id: std::sync::Mutex<Option<i64>>,
+}
+
+impl MemoryCheckpointStore {
+    /// Create new empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn load(&self) -> Result<Option<i64>, Error> {
+        Ok(*self
+            .id
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner))
+    }
+
+    fn save(&self, id: i64) -> Result<(), Error> {
+        *self
+            .id
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(id);
+        Ok(())
+    }
+}
+
+/// Checkpoint store keeping the movement ID in a plain text file
+///
+/// The file is written to a temporary sibling and renamed into place, so a
+/// crash never leaves a truncated checkpoint behind.
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    /// Create new file store
+    /// # Arguments
+    /// * `path` - Path of the checkpoint file, created on first save
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CheckpointStore for FileCheckpointStore {
+    fn load(&self) -> Result<Option<i64>, Error> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => content
+                .trim()
+                .parse()
+                .map(Some)
+                .map_err(|_| Error::CorruptCheckpoint(content.trim().to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, id: i64) -> Result<(), Error> {
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, id.to_string())?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Outcome of a single [`SyncEngine::run`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SyncReport {
+    /// Number of movements handed to the callback
+    pub delivered: usize,
+    /// Number of movements skipped because they were already processed
+    pub skipped: usize,
+    /// Whether the server bookmark was ahead of the local checkpoint and was
+    /// reset to it
+    pub repaired: bool,
+    /// Local checkpoint after the run
+    pub checkpoint: Option<i64>,
+}
+
+/// Delivers each movement exactly once using a local checkpoint
+pub struct SyncEngine<S: CheckpointStore> {
+    fio: Fio,
+    store: S,
+}
+
+impl<S: CheckpointStore> SyncEngine<S> {
+    /// Create new sync engine
+    /// # Arguments
+    /// * `fio` - API client
+    /// * `store` - Checkpoint storage
+    #[must_use]
+    pub const fn new(fio: Fio, store: S) -> Self {
+        Self { fio, store }
+    }
+
+    /// Checkpoint storage used by the engine
+    #[must_use]
+    pub const fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Download new movements and hand them to `handler` in ID order
+    ///
+    /// Usually a single download. When the bookmark was ahead of the
+    /// checkpoint, for example after a crashed run, it is reset to the
+    /// checkpoint and the movements are pulled again. The checkpoint is saved
+    /// after every
+    /// successful callback. When the callback fails, the run stops and the
+    /// failed movement is delivered again on the next run.
+    /// # Arguments
+    /// * `handler` - Callback invoked once per new movement
+    /// # Returns
+    /// * `SyncReport` - Summary of the run
+    /// # Errors
+    /// * `Error::Handler` - The callback failed
+    /// * `Error::Limit` - Too many requests
+    /// * `Error::Io` / `Error::CorruptCheckpoint` - Checkpoint store failure
+    pub async fn run<F>(&self, mut handler: F) -> Result<SyncReport, Error>
+    where
+        F: FnMut(&Movement) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let checkpoint = self.store.load()?;
+        let mut report = SyncReport {
+            checkpoint,
+            ..SyncReport::default()
+        };
+        let mut statement = self.fio.movements_since_last().await?;
+
+        let server = statement.account_statement.info.id_last_download;
+        if let (true, Some(id)) = (needs_repair(checkpoint, server), checkpoint) {
+            if self.fio.is_dry_run() {
+                warn!(
+                    "Server bookmark {server:?} is ahead of local checkpoint {id}, \
+                     dry run cannot reset it"
+                );
+            } else {
+                warn!("Server bookmark {server:?} is ahead of local checkpoint {id}, resetting");
+                self.fio.set_last_id(&id.to_string()).await?;
+                statement = self.fio.movements_since_last().await?;
+                report.repaired = true;
+            }
+        }
+
+        let mut movements = statement.movements()?;
+        movements.sort_by_key(|m| m.id);
+        for movement in &movements {
+            if checkpoint.is_some_and(|id| movement.id <= id) {
+                debug!("Skipping already processed movement {}", movement.id);
+                report.skipped += 1;
+                continue;
+            }
+            handler(movement).map_err(Error::Handler)?;
+            self.store.save(movement.id)?;
+            report.checkpoint = Some(movement.id);
+            report.delivered += 1;
+        }
+        info!(
+            "Sync delivered {} movements, skipped {}",
+            report.delivered, report.skipped
+        );
+        Ok(report)
+    }
+}
+
+/// The server bookmark is ahead of the local checkpoint, meaning movements
+/// were downloaded but never processed, for example because the reset was
+/// only logged in dry-run mode
+const fn needs_repair(checkpoint: Option<i64>, server: Option<i64>) -> bool {
+    match (checkpoint, server) {
+        (Some(local), Some(server)) => server > local,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::last_json;
+    use crate::mock::MockServer;
+
+    #[tokio::test(start_paused = true)]
+    async fn first_run_pulls_once() {
+        let server = MockServer::start(|_, _| (200, last_json(&[11, 12], None))).await;
+        let engine = SyncEngine::new(server.fio("token"), MemoryCheckpointStore::new());
+        let mut seen = vec![];
+        let report = engine
+            .run(|m| {
+                seen.push(m.id);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(seen, [11, 12]);
+        assert!(!report.repaired);
+        assert_eq!(report.checkpoint, Some(12));
+        assert_eq!(server.requests(), ["GET /last/token/transactions.json"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn checkpoint_in_step_pulls_once() {
+        let server = MockServer::start(|_, _| (200, last_json(&[13], Some(12)))).await;
+        let store = MemoryCheckpointStore::new();
+        store.save(12).unwrap();
+        let engine = SyncEngine::new(server.fio("token"), store);
+        let report = engine.run(|_| Ok(())).await.unwrap();
+        assert!(!report.repaired);
+        assert_eq!(report.delivered, 1);
+        assert_eq!(server.requests(), ["GET /last/token/transactions.json"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn run_repairs_bookmark_ahead() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // A crashed run downloaded up to 14 but only processed up to 12
+        let pulls = AtomicUsize::new(0);
+        let server = MockServer::start(move |_, path| {
+            if path.starts_with("/set-last-id/") {
+                (200, vec![])
+            } else if pulls.fetch_add(1, Ordering::SeqCst) == 0 {
+                (200, last_json(&[15], Some(14)))
+            } else {
+                (200, last_json(&[12, 13, 14, 15], Some(12)))
+            }
+        })
+        .await;
+        let store = MemoryCheckpointStore::new();
+        store.save(12).unwrap();
+        let engine = SyncEngine::new(server.fio("token"), store);
+        let mut seen = vec![];
+        let report = engine
+            .run(|m| {
+                seen.push(m.id);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            server.requests(),
+            [
+                "GET /last/token/transactions.json",
+                "GET /set-last-id/token/12/",
+                "GET /last/token/transactions.json"
+            ]
+        );
+        assert_eq!(seen, [13, 14, 15]);
+        assert_eq!(report.skipped, 1);
+        assert!(report.repaired);
+        assert_eq!(engine.store().load().unwrap(), Some(15));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_callback_keeps_checkpoint() {
+        let server = MockServer::start(|_, path| {
+            if path.starts_with("/set-last-id/") {
+                (200, vec![])
+            } else {
+                (200, last_json(&[11, 12], Some(10)))
+            }
+        })
+        .await;
+        let store = MemoryCheckpointStore::new();
+        store.save(10).unwrap();
+        let engine = SyncEngine::new(server.fio("token"), store);
+        let result = engine
+            .run(|m| {
+                if m.id == 12 {
+                    Err("database down".into())
+                } else {
+                    Ok(())
+                }
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Handler(_))));
+        assert_eq!(engine.store().load().unwrap(), Some(11));
+    }
+
+    #[test]
+    fn repair_only_when_server_ahead() {
+        assert!(needs_repair(Some(10), Some(12)));
+        assert!(!needs_repair(Some(12), Some(12)));
+        assert!(!needs_repair(Some(12), Some(10)));
+        assert!(!needs_repair(None, Some(10)));
+        assert!(!needs_repair(Some(10), None));
+    }
+
+    #[test]
+    fn memory_store_round_trip() {
+        let store = MemoryCheckpointStore::new();
+        assert_eq!(store.load().unwrap(), None);
+        store.save(42).unwrap();
+        assert_eq!(store.load().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("fiocz-sync-{}", std::process::id()));
+        let store = FileCheckpointStore::new(&path);
+        assert_eq!(store.load().unwrap(), None);
+        store.save(24_230_217_199).unwrap();
+        assert_eq!(store.load().unwrap(), Some(24_230_217_199));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn file_store_corrupt() {
+        let path = std::env::temp_dir().join(format!("fiocz-sync-bad-{}", std::process::id()));
+        std::fs::write(&path, "not a number").unwrap();
+        let store = FileCheckpointStore::new(&path);
+        assert!(matches!(store.load(), Err(Error::CorruptCheckpoint(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+//! Czech domestic account numbers
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::bank_code::{self, Bank};
+
+/// ČNB weights for the mod 11 checksum, applied to the number padded to 10 digits
+const WEIGHTS: [u64; 10] = [6, 3, 7, 9, 10, 5, 8, 4, 2, 1];
+
+/// Czech domestic account number (`prefix-number/bankcode`)
+///
+/// Parsing validates the lengths of all parts and the ČNB mod 11 weighted
+/// checksum of both the prefix and the base number.
+///
+/// ```
+/// use fiocz_rs::types::account_number::CzAccountNumber;
+///
+/// let account: CzAccountNumber = "19-2000145399/0800".parse().unwrap();
+/// assert_eq!(account.account(), "19-2000145399");
+/// assert_eq!(account.bank_code(), "0800");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CzAccountNumber {
+    prefix: u32,
+    number: u64,
+    bank_code: String,
+}
+
+impl CzAccountNumber {
+    /// Create account number from its parts
+    /// # Arguments
+    /// * `prefix` - Prefix (up to 6 digits), `0` if none
+    /// * `number` - Base number (2 to 10 digits)
+    /// * `bank_code` - Four digit bank code
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - Invalid length or checksum
+    pub fn new(prefix: u32, number: u64, bank_code: &str) -> Result<Self, Error> {
+        if prefix > 999_999 {
+            return Err(invalid(format!("prefix {prefix} is longer than 6 digits")));
+        }
+        if number > 9_999_999_999 {
+            return Err(invalid(format!("number {number} is longer than 10 digits")));
+        }
+        if number.to_string().bytes().filter(|&b| b != b'0').count() < 2 {
+            return Err(invalid(format!(
+                "number {number} needs at least two non-zero digits"
+            )));
+        }
+        if bank_code.len() != 4 || !bank_code.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid(format!("bank code {bank_code} is not 4 digits")));
+        }
+        if !checksum_valid(u64::from(prefix)) {
+            return Err(invalid(format!("prefix {prefix} fails the mod 11 check")));
+        }
+        if !checksum_valid(number) {
+            return Err(invalid(format!("number {number} fails the mod 11 check")));
+        }
+        Ok(Self {
+            prefix,
+            number,
+            bank_code: bank_code.to_string(),
+        })
+    }
+
+    /// Parse an account without bank code (`prefix-number`) at the given bank
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - Invalid length, characters or checksum
+    pub fn parse_with_bank(account: &str, bank_code: &str) -> Result<Self, Error> {
+        let account = account.trim();
+        let (prefix, number) = match account.split_once('-') {
+            Some((prefix, number)) => (parse_digits(prefix, 6)?, number),
+            None => (0, account),
+        };
+        let prefix = u32::try_from(prefix)
+            .map_err(|_| invalid(format!("{account} has an invalid prefix")))?;
+        Self::new(prefix, parse_digits(number, 10)?, bank_code)
+    }
+
+    /// Prefix, `None` if the account has none
+    #[must_use]
+    pub const fn prefix(&self) -> Option<u32> {
+        if self.prefix == 0 {
+            None
+        } else {
+            Some(self.prefix)
+        }
+    }
+
+    /// Base number
+    #[must_use]
+    pub const fn number(&self) -> u64 {
+        self.number
+    }
+
+    /// Four digit bank code
+    #[must_use]
+    pub fn bank_code(&self) -> &str {
+        &self.bank_code
+    }
+
+    /// Bank from the ČNB bank code list, `None` if the code is not registered
+    #[must_use]
+    pub fn bank(&self) -> Option<&'static Bank> {
+        bank_code::lookup(&self.bank_code)
+    }
+
+    /// Account part without bank code (`prefix-number`), as used by the
+    /// import `accountTo` / `accountFrom` fields
+    #[must_use]
+    pub fn account(&self) -> String {
+        match self.prefix() {
+            Some(prefix) => format!("{prefix}-{}", self.number),
+            None => self.number.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CzAccountNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.account(), self.bank_code)
+    }
+}
+
+impl FromStr for CzAccountNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (account, bank_code) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| invalid(format!("{s} has no bank code")))?;
+        Self::parse_with_bank(account, bank_code.trim())
+    }
+}
+
+impl TryFrom<String> for CzAccountNumber {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<CzAccountNumber> for String {
+    fn from(value: CzAccountNumber) -> Self {
+        value.to_string()
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::InvalidAccountNumber(message)
+}
+
+fn parse_digits(value: &str, max_len: usize) -> Result<u64, Error> {
+    if value.is_empty() || value.len() > max_len || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid(format!(
+            "{value} is not a number of up to {max_len} digits"
+        )));
+    }
+    value
+        .parse()
+        .map_err(|_| invalid(format!("{value} is not a number")))
+}
+
+/// ČNB mod 11 check of a number padded with leading zeros to 10 digits
+const fn checksum_valid(mut value: u64) -> bool {
+    let mut sum = 0;
+    let mut i = WEIGHTS.len();
+    while i > 0 {
+        i -= 1;
+        sum += (value % 10) * WEIGHTS[i];
+        value /= 10;
+    }
+    sum % 11 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_with_prefix() {
+        let account: CzAccountNumber = "19-2000145399/0800".parse().unwrap();
+        assert_eq!(account.prefix(), Some(19));
+        assert_eq!(account.number(), 2_000_145_399);
+        assert_eq!(account.bank_code(), "0800");
+        assert_eq!(account.to_string(), "19-2000145399/0800");
+    }
+
+    #[test]
+    fn parse_without_prefix() {
+        let account: CzAccountNumber = "2101179627/2010".parse().unwrap();
+        assert_eq!(account.prefix(), None);
+        assert_eq!(account.account(), "2101179627");
+    }
+
+    #[test]
+    fn leading_zeros_are_normalised() {
+        assert!(
+            "000019-0002000145399/0800"
+                .parse::<CzAccountNumber>()
+                .is_err(),
+            "base number longer than 10 digits"
+        );
+        let account: CzAccountNumber = "000019-2000145399/0800".parse().unwrap();
+        assert_eq!(account.to_string(), "19-2000145399/0800");
+    }
+
+    #[test]
+    fn checksum_failures() {
+        assert!(matches!(
+            "19-2000145398/0800".parse::<CzAccountNumber>(),
+            Err(Error::InvalidAccountNumber(_))
+        ));
+        assert!(matches!(
+            "18-2000145399/0800".parse::<CzAccountNumber>(),
+            Err(Error::InvalidAccountNumber(_))
+        ));
+    }
+
+    #[test]
+    fn format_failures() {
+        for input in [
+            "2101179627",
+            "2101179627/201",
+            "2101179627/20x0",
+            "24002a2222/2010",
+            "1234567-2101179627/2010",
+            "12345678901/2010",
+            "10/2010",
+            "-2101179627/2010",
+        ] {
+            assert!(input.parse::<CzAccountNumber>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn serde_as_string() {
+        let account: CzAccountNumber = "19-2000145399/0800".parse().unwrap();
+        let json = serde_json::to_string(&account).unwrap();
+        assert_eq!(json, "\"19-2000145399/0800\"");
+        let back: CzAccountNumber = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, account);
+        assert!(serde_json::from_str::<CzAccountNumber>("\"1/0800\"").is_err());
+    }
+}
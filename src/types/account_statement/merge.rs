@@ -0,0 +1,201 @@
+//! Merging of overlapping downloads
+
+use chrono::NaiveDate;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use super::{day, AccountStatement, Info, Statement, TransactionList};
+use crate::error::Error;
+use crate::types::movement::{Movement, RawMovement};
+
+impl Statement {
+    /// Merge two downloads of the same account into one statement
+    ///
+    /// Movements are de-duplicated by movement ID and ordered by ID. The
+    /// merged [`Info`] covers both periods: the opening balance comes from
+    /// the statement starting first, the closing balance from the one ending
+    /// last, and `id_from`/`id_to` are recomputed from the merged movements.
+    /// The periods must overlap or be adjacent.
+    /// # Arguments
+    /// * `other` - Statement to merge with
+    /// # Returns
+    /// * `Statement` - Merged statement
+    /// # Errors
+    /// * `Error::AccountMismatch` - The statements belong to different accounts
+    /// * `Error::MergeGap` - The periods neither overlap nor follow each other,
+    ///   so movements between them would be missing
+    /// * `Error::MergeConflict` - The same movement ID has different contents
+    /// * `Error::InvalidResponse` - A movement has no ID
+    pub fn merge(&self, other: &Self) -> Result<Self, Error> {
+        let a = &self.account_statement.info;
+        let b = &other.account_statement.info;
+        if a.account_id != b.account_id || a.bank_id != b.bank_id {
+            return Err(Error::AccountMismatch(
+                format!("{}/{}", a.account_id, a.bank_id),
+                format!("{}/{}", b.account_id, b.bank_id),
+            ));
+        }
+
+        let (earlier, later) = if day(&b.date_start) < day(&a.date_start) {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        let parse = |date: &str| NaiveDate::parse_from_str(day(date), "%Y-%m-%d").ok();
+        if let (Some(end), Some(start)) = (parse(&earlier.date_end), parse(&later.date_start)) {
+            if end.succ_opt().is_some_and(|next| start > next) {
+                return Err(Error::MergeGap(
+                    day(&earlier.date_end).to_string(),
+                    day(&later.date_start).to_string(),
+                ));
+            }
+        }
+
+        let mut by_id: BTreeMap<i64, &RawMovement> = BTreeMap::new();
+        let mut conflicts = Vec::new();
+        for raw in self
+            .account_statement
+            .transaction_list
+            .transaction
+            .iter()
+            .chain(&other.account_statement.transaction_list.transaction)
+        {
+            let id = Movement::from_raw(raw)?.id;
+            match by_id.entry(id) {
+                Entry::Vacant(entry) => {
+                    entry.insert(raw);
+                }
+                Entry::Occupied(entry) => {
+                    if *entry.get() != raw && !conflicts.contains(&id) {
+                        conflicts.push(id);
+                    }
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(Error::MergeConflict(conflicts));
+        }
+
+        let first = earlier;
+        let last = if day(&b.date_end) > day(&a.date_end) {
+            b
+        } else {
+            a
+        };
+        let info = Info {
+            opening_balance: first.opening_balance,
+            date_start: first.date_start.clone(),
+            closing_balance: last.closing_balance,
+            date_end: last.date_end.clone(),
+            year_list: if a.year_list == b.year_list {
+                a.year_list.clone()
+            } else {
+                serde_json::Value::Null
+            },
+            id_list: if a.id_list == b.id_list {
+                a.id_list
+            } else {
+                None
+            },
+            id_from: by_id.keys().next().copied(),
+            id_to: by_id.keys().next_back().copied(),
+            id_last_download: a.id_last_download.max(b.id_last_download),
+            ..a.clone()
+        };
+
+        Ok(Self {
+            account_statement: AccountStatement {
+                info,
+                transaction_list: TransactionList {
+                    transaction: by_id.into_values().cloned().collect(),
+                },
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{movement_json, statement};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn merge_overlapping_deduplicates() {
+        let january = statement(
+            "2400222222",
+            "2024-01-01",
+            "2024-01-31",
+            "1000.0",
+            "1150.0",
+            &[
+                movement_json(10, "2024-01-05", "100.0", "a"),
+                movement_json(11, "2024-01-20", "50.0", "b"),
+            ],
+        );
+        let later = statement(
+            "2400222222",
+            "2024-01-15",
+            "2024-02-10",
+            "1100.0",
+            "1130.0",
+            &[
+                movement_json(11, "2024-01-20", "50.0", "b"),
+                movement_json(12, "2024-02-01", "-20.0", "c"),
+            ],
+        );
+        let merged = later.merge(&january).unwrap();
+        let info = &merged.account_statement.info;
+        let ids: Vec<i64> = merged.movements().unwrap().iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![10, 11, 12]);
+        assert_eq!(info.id_from, Some(10));
+        assert_eq!(info.id_to, Some(12));
+        assert_eq!(info.date_start, "2024-01-01+0100");
+        assert_eq!(info.date_end, "2024-02-10+0100");
+        assert_eq!(info.opening_balance, Decimal::new(1000, 0));
+        assert_eq!(info.closing_balance, Decimal::new(1130, 0));
+    }
+
+    #[test]
+    fn merge_detects_conflicts() {
+        let a = statement(
+            "1",
+            "2024-01-01",
+            "2024-01-31",
+            "0",
+            "0",
+            &[movement_json(10, "2024-01-05", "100.0", "a")],
+        );
+        let b = statement(
+            "1",
+            "2024-01-01",
+            "2024-01-31",
+            "0",
+            "0",
+            &[movement_json(10, "2024-01-05", "100.0", "changed")],
+        );
+        assert!(matches!(a.merge(&b), Err(Error::MergeConflict(ids)) if ids == vec![10]));
+    }
+
+    #[test]
+    fn merge_requires_adjacent_periods() {
+        let january = statement("1", "2024-01-01", "2024-01-31", "0", "10", &[]);
+        let february = statement("1", "2024-02-01", "2024-02-29", "10", "20", &[]);
+        let march = statement("1", "2024-03-02", "2024-03-31", "20", "30", &[]);
+        let merged = february.merge(&january).unwrap();
+        assert_eq!(merged.account_statement.info.date_start, "2024-01-01+0100");
+        assert_eq!(merged.account_statement.info.date_end, "2024-02-29+0100");
+        assert!(matches!(
+            march.merge(&merged),
+            Err(Error::MergeGap(end, start)) if end == "2024-02-29" && start == "2024-03-02"
+        ));
+        assert!(matches!(january.merge(&march), Err(Error::MergeGap(_, _))));
+    }
+
+    #[test]
+    fn merge_rejects_other_account() {
+        let a = statement("1", "2024-01-01", "2024-01-31", "0", "0", &[]);
+        let b = statement("2", "2024-01-01", "2024-01-31", "0", "0", &[]);
+        assert!(matches!(a.merge(&b), Err(Error::AccountMismatch(_, _))));
+    }
+}
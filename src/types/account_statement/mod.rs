@@ -0,0 +1,350 @@
+//! Account statement types
+mod merge;
+mod verify;
+
+pub use verify::{BalanceMismatch, VerificationReport};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::bic::Bic;
+use crate::types::currency::{Currency, Money};
+use crate::types::iban::Iban;
+use crate::types::movement::Movement;
+
+/// Holder for account statement
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statement {
+    /// Account statement
+    pub account_statement: AccountStatement,
+}
+
+impl Statement {
+    /// Parse all movements in the statement into typed [`Movement`]s
+    /// # Returns
+    /// * `Vec<Movement>` - Movements in the order returned by the API
+    /// # Errors
+    /// * `Error::InvalidResponse` - A movement is missing a mandatory column
+    pub fn movements(&self) -> Result<Vec<Movement>, Error> {
+        self.account_statement
+            .transaction_list
+            .transaction
+            .iter()
+            .map(Movement::from_raw)
+            .collect()
+    }
+}
+
+/// Account statement
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStatement {
+    /// Account info
+    pub info: Info,
+    /// Transaction list
+    pub transaction_list: TransactionList,
+}
+
+/// Account info
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Info {
+    /// Account ID
+    pub account_id: String,
+    /// Bank ID
+    pub bank_id: String,
+    /// Currency
+    pub currency: Currency,
+    /// IBAN, `None` when missing or invalid
+    #[serde(default, deserialize_with = "crate::types::lenient")]
+    pub iban: Option<Iban>,
+    /// BIC, `None` when missing or invalid
+    #[serde(default, deserialize_with = "crate::types::lenient")]
+    pub bic: Option<Bic>,
+    /// Opening balance
+    pub opening_balance: Decimal,
+    /// Closing balance
+    pub closing_balance: Decimal,
+    /// Date start
+    pub date_start: String,
+    /// Date end
+    pub date_end: String,
+    /// Year list
+    pub year_list: Value,
+    /// ID list
+    pub id_list: Option<i64>,
+    /// ID from
+    pub id_from: Option<i64>,
+    /// ID to
+    pub id_to: Option<i64>,
+    /// ID last download
+    pub id_last_download: Option<i64>,
+}
+
+impl Info {
+    /// Opening balance in the account currency
+    #[must_use]
+    pub fn opening(&self) -> Money {
+        Money::new(self.opening_balance, self.currency)
+    }
+
+    /// Closing balance in the account currency
+    #[must_use]
+    pub fn closing(&self) -> Money {
+        Money::new(self.closing_balance, self.currency)
+    }
+}
+
+/// Transaction list
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionList {
+    /// Transactions
+    pub transaction: Vec<HashMap<String, Option<TransactionData>>>,
+}
+
+/// Transaction data
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionData {
+    /// Transaction Data Value
+    pub value: TransactionDataEnum,
+    /// Transaction Data Name
+    pub name: String,
+    /// Transaction Data Id
+    pub id: i64,
+}
+
+/// Transaction data enum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionDataEnum {
+    /// Transaction data int value
+    Integer(i64),
+    /// Transaction data string value
+    String(String),
+    /// Transaction data float value
+    Decimal(Decimal),
+}
+
+/// Official statement identifier (year and sequence number within the year)
+///
+/// Returned by [`crate::Fio::last_statement_id`] and accepted by
+/// [`crate::Fio::statements`]. Parses from and displays as the API's
+/// `year,id` text form, e.g. `2024,3`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LastStatementId {
+    /// Year of the statement
+    pub year: u16,
+    /// Statement ID
+    pub id: u32,
+}
+
+impl LastStatementId {
+    /// Create new statement identifier
+    #[must_use]
+    pub const fn new(year: u16, id: u32) -> Self {
+        Self { year, id }
+    }
+
+    /// Iterate over all statements of the same year preceding this one
+    pub fn preceding(&self) -> impl Iterator<Item = Self> {
+        let year = self.year;
+        (1..self.id).map(move |id| Self { year, id })
+    }
+}
+
+impl fmt::Display for LastStatementId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.year, self.id)
+    }
+}
+
+impl FromStr for LastStatementId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidResponse(format!("Invalid statement id: {}", s.trim()));
+        let (year, id) = s.trim().split_once(',').ok_or_else(invalid)?;
+        let year = year.trim();
+        let id = id.trim();
+        if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            year: year.parse().map_err(|_| invalid())?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Date part (`YYYY-MM-DD`) of an API date such as `2024-01-15+0100`
+fn day(date: &str) -> &str {
+    date.get(..10).unwrap_or(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{movement_json, statement};
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn transaction_data_enum_deserialize_integer() {
+        let json = "42";
+        let result: Result<TransactionDataEnum, _> = serde_json::from_str(json);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), TransactionDataEnum::Integer(42));
+    }
+
+    #[test]
+    fn transaction_data_enum_deserialize_string() {
+        let json = "\"hello\"";
+        let result: Result<TransactionDataEnum, _> = serde_json::from_str(json);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            TransactionDataEnum::String("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn transaction_data_enum_deserialize_decimal() {
+        let json = "123.45";
+        let result: Result<TransactionDataEnum, _> = serde_json::from_str(json);
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap(),
+            TransactionDataEnum::Decimal(Decimal::new(12345, 2))
+        );
+    }
+
+    #[test]
+    fn last_statement_id_creation() {
+        let id = LastStatementId::new(2024, 1);
+        assert_eq!(id.year, 2024);
+        assert_eq!(id.id, 1);
+    }
+
+    #[test]
+    fn last_statement_id_parse() {
+        assert_eq!(
+            "2024,01\n".parse::<LastStatementId>().unwrap(),
+            LastStatementId::new(2024, 1)
+        );
+        assert_eq!(
+            " 2023 , 12 ".parse::<LastStatementId>().unwrap(),
+            LastStatementId::new(2023, 12)
+        );
+        assert!("2024".parse::<LastStatementId>().is_err());
+        assert!("24,1".parse::<LastStatementId>().is_err());
+        assert!("2024,x".parse::<LastStatementId>().is_err());
+        assert!("2024,".parse::<LastStatementId>().is_err());
+    }
+
+    #[test]
+    fn last_statement_id_display_round_trip() {
+        let id = LastStatementId::new(2024, 7);
+        assert_eq!(id.to_string(), "2024,7");
+        assert_eq!(id.to_string().parse::<LastStatementId>().unwrap(), id);
+    }
+
+    #[test]
+    fn last_statement_id_clone_eq() {
+        let id1 = LastStatementId::new(2024, 1);
+        let id2 = id1;
+        assert_eq!(id1, id2);
+        assert_ne!(id1, LastStatementId::new(2024, 2));
+    }
+
+    #[test]
+    fn last_statement_id_ordering() {
+        assert!(LastStatementId::new(2023, 12) < LastStatementId::new(2024, 1));
+        assert!(LastStatementId::new(2024, 2) < LastStatementId::new(2024, 10));
+    }
+
+    #[test]
+    fn last_statement_id_preceding() {
+        let ids: Vec<_> = LastStatementId::new(2024, 3).preceding().collect();
+        assert_eq!(
+            ids,
+            vec![LastStatementId::new(2024, 1), LastStatementId::new(2024, 2)]
+        );
+        assert_eq!(LastStatementId::new(2024, 1).preceding().count(), 0);
+    }
+
+    #[test]
+    fn transaction_data_enum_round_trip_integer() {
+        let original = TransactionDataEnum::Integer(99);
+        let json = serde_json::to_string(&original);
+        assert!(json.is_ok());
+        let deserialized: Result<TransactionDataEnum, _> = serde_json::from_str(&json.unwrap());
+        assert!(deserialized.is_ok());
+        assert_eq!(deserialized.unwrap(), original);
+    }
+
+    #[test]
+    fn transaction_data_enum_round_trip_string() {
+        let original = TransactionDataEnum::String("test value".to_string());
+        let json = serde_json::to_string(&original);
+        assert!(json.is_ok());
+        let deserialized: Result<TransactionDataEnum, _> = serde_json::from_str(&json.unwrap());
+        assert!(deserialized.is_ok());
+        assert_eq!(deserialized.unwrap(), original);
+    }
+
+    #[test]
+    fn unknown_currency_parses() {
+        let czk = statement(
+            "2101179627",
+            "2024-01-01",
+            "2024-01-31",
+            "0",
+            "100",
+            &[movement_json(1, "2024-01-02", "100", "x")],
+        );
+        let json = serde_json::to_string(&czk).unwrap().replace("CZK", "ZWG");
+        let parsed: Statement = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.account_statement.info.currency, Currency::Zwg);
+        let json = json.replace("ZWG", "XTS");
+        let parsed: Statement = serde_json::from_str(&json).unwrap();
+        let other: Currency = "XTS".parse().unwrap();
+        assert!(!other.is_known());
+        assert_eq!(parsed.account_statement.info.currency, other);
+        assert_eq!(parsed.movements().unwrap()[0].currency, other);
+    }
+
+    #[test]
+    fn info_tolerates_invalid_iban_and_bic() {
+        let valid = statement("2101179627", "2024-01-01", "2024-01-31", "0", "0", &[]);
+        assert_eq!(
+            valid
+                .account_statement
+                .info
+                .iban
+                .as_ref()
+                .unwrap()
+                .to_string(),
+            "CZ5020100000002101179627"
+        );
+        let json = serde_json::to_string(&valid)
+            .unwrap()
+            .replace("CZ5020100000002101179627", "CZ00 broken")
+            .replace("FIOBCZPPXXX", "?");
+        let parsed: Statement = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.account_statement.info.iban, None);
+        assert_eq!(parsed.account_statement.info.bic, None);
+        let round_trip: Statement =
+            serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+        assert_eq!(round_trip, parsed);
+    }
+}
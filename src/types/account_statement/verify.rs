@@ -0,0 +1,165 @@
+//! Integrity checks of downloaded statements
+
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+use super::{day, Statement};
+use crate::types::movement::Movement;
+
+/// Closing balance disagreeing with the movements
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BalanceMismatch {
+    /// Closing balance reported in [`super::Info`]
+    pub reported: Decimal,
+    /// Opening balance plus the sum of all movement amounts
+    pub computed: Decimal,
+}
+
+/// Result of [`Statement::verify`]
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct VerificationReport {
+    /// Number of movements checked
+    pub movement_count: usize,
+    /// Set when the closing balance does not match the movements
+    pub balance_mismatch: Option<BalanceMismatch>,
+    /// Movement IDs occurring more than once
+    pub duplicate_ids: Vec<i64>,
+    /// Boundary IDs (`id_from` / `id_to`) declared in the info but missing
+    /// from the movements, a sign of a truncated download
+    pub missing_boundary_ids: Vec<i64>,
+    /// Movement IDs outside the declared `id_from`..=`id_to` range
+    pub ids_out_of_range: Vec<i64>,
+    /// Movement IDs dated outside the statement period
+    pub dates_out_of_range: Vec<i64>,
+    /// Number of movements missing mandatory columns
+    pub unparsable: usize,
+}
+
+impl VerificationReport {
+    /// Whether no problem was found
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.balance_mismatch.is_none()
+            && self.duplicate_ids.is_empty()
+            && self.missing_boundary_ids.is_empty()
+            && self.ids_out_of_range.is_empty()
+            && self.dates_out_of_range.is_empty()
+            && self.unparsable == 0
+    }
+}
+
+impl Statement {
+    /// Check the statement for truncation and corruption
+    ///
+    /// Verifies that the opening balance plus all movement amounts equals the
+    /// closing balance, that movement IDs are unique and cover the declared
+    /// `id_from`/`id_to` range, and that every movement is dated within the
+    /// statement period. Movement IDs are assigned bank-wide, so gaps between
+    /// consecutive IDs of one account are expected and not reported.
+    /// # Returns
+    /// * `VerificationReport` - Structured list of problems found
+    #[must_use]
+    pub fn verify(&self) -> VerificationReport {
+        let info = &self.account_statement.info;
+        let raw = &self.account_statement.transaction_list.transaction;
+        let mut report = VerificationReport {
+            movement_count: raw.len(),
+            ..VerificationReport::default()
+        };
+
+        let movements: Vec<Movement> = raw
+            .iter()
+            .filter_map(|m| Movement::from_raw(m).ok())
+            .collect();
+        report.unparsable = raw.len() - movements.len();
+
+        let computed = info.opening_balance + movements.iter().map(|m| m.amount).sum::<Decimal>();
+        if computed != info.closing_balance {
+            report.balance_mismatch = Some(BalanceMismatch {
+                reported: info.closing_balance,
+                computed,
+            });
+        }
+
+        let mut seen = HashSet::new();
+        for movement in &movements {
+            if !seen.insert(movement.id) && !report.duplicate_ids.contains(&movement.id) {
+                report.duplicate_ids.push(movement.id);
+            }
+            if info.id_from.is_some_and(|from| movement.id < from)
+                || info.id_to.is_some_and(|to| movement.id > to)
+            {
+                report.ids_out_of_range.push(movement.id);
+            }
+            let date = movement.day();
+            if date < day(&info.date_start) || date > day(&info.date_end) {
+                report.dates_out_of_range.push(movement.id);
+            }
+        }
+        for id in [info.id_from, info.id_to].into_iter().flatten() {
+            if !seen.contains(&id) && !report.missing_boundary_ids.contains(&id) {
+                report.missing_boundary_ids.push(id);
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{movement_json, statement};
+
+    fn january(movements: &[String]) -> Statement {
+        let mut s = statement(
+            "1",
+            "2024-01-01",
+            "2024-01-31",
+            "1000.0",
+            "1080.0",
+            movements,
+        );
+        s.account_statement.info.id_from = Some(10);
+        s.account_statement.info.id_to = Some(12);
+        s
+    }
+
+    #[test]
+    fn verify_consistent() {
+        let report = january(&[
+            movement_json(10, "2024-01-05", "100.0", "a"),
+            movement_json(12, "2024-01-20", "-20.0", "b"),
+        ])
+        .verify();
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.movement_count, 2);
+    }
+
+    #[test]
+    fn verify_truncated() {
+        let report = january(&[movement_json(10, "2024-01-05", "100.0", "a")]).verify();
+        assert!(!report.is_ok());
+        assert_eq!(report.missing_boundary_ids, vec![12]);
+        assert_eq!(
+            report.balance_mismatch,
+            Some(BalanceMismatch {
+                reported: Decimal::new(1080, 0),
+                computed: Decimal::new(1100, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn verify_duplicates_and_ranges() {
+        let report = january(&[
+            movement_json(10, "2024-01-05", "100.0", "a"),
+            movement_json(10, "2024-01-05", "100.0", "a"),
+            movement_json(12, "2024-02-02", "-20.0", "b"),
+            movement_json(13, "2024-01-30", "-100.0", "c"),
+        ])
+        .verify();
+        assert_eq!(report.duplicate_ids, vec![10]);
+        assert_eq!(report.dates_out_of_range, vec![12]);
+        assert_eq!(report.ids_out_of_range, vec![13]);
+    }
+}
@@ -0,0 +1,270 @@
+//! Czech bank codes
+//!
+//! Embedded copy of the ČNB list of payment system participant codes, so
+//! bank codes can be validated and resolved to names and BICs offline.
+use crate::types::bic::Bic;
+
+/// Czech bank registered in the ČNB bank code list
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Bank {
+    /// Four digit bank code
+    pub code: &'static str,
+    /// Registered name
+    pub name: &'static str,
+    /// SWIFT code, if the institution has one
+    pub bic: Option<&'static str>,
+    /// Whether the bank takes part in CERTIS instant payments
+    pub instant_payments: bool,
+}
+
+impl Bank {
+    /// Parsed SWIFT code
+    #[must_use]
+    pub fn bic(&self) -> Option<Bic> {
+        self.bic.and_then(|bic| bic.parse().ok())
+    }
+}
+
+const fn bank(
+    code: &'static str,
+    name: &'static str,
+    bic: Option<&'static str>,
+    instant_payments: bool,
+) -> Bank {
+    Bank {
+        code,
+        name,
+        bic,
+        instant_payments,
+    }
+}
+
+/// Registered banks sorted by code
+const BANKS: &[Bank] = &[
+    bank("0100", "Komerční banka, a.s.", Some("KOMBCZPP"), true),
+    bank(
+        "0300",
+        "Československá obchodní banka, a. s.",
+        Some("CEKOCZPP"),
+        true,
+    ),
+    bank("0600", "MONETA Money Bank, a.s.", Some("AGBACZPP"), true),
+    bank("0710", "ČESKÁ NÁRODNÍ BANKA", Some("CNBACZPP"), false),
+    bank("0800", "Česká spořitelna, a.s.", Some("GIBACZPX"), true),
+    bank("2010", "Fio banka, a.s.", Some("FIOBCZPP"), true),
+    bank(
+        "2060",
+        "Citfin, spořitelní družstvo",
+        Some("CITFCZPP"),
+        false,
+    ),
+    bank("2070", "TRINITY BANK a.s.", Some("MPUBCZPP"), true),
+    bank("2100", "Hypoteční banka, a.s.", None, false),
+    bank("2200", "Peněžní dům, spořitelní družstvo", None, false),
+    bank(
+        "2220",
+        "Artesa, spořitelní družstvo",
+        Some("ARTTCZPP"),
+        false,
+    ),
+    bank("2250", "Banka CREDITAS a.s.", Some("CTASCZ22"), true),
+    bank("2260", "NEY spořitelní družstvo", None, false),
+    bank("2275", "Podnikatelská družstevní záložna", None, false),
+    bank(
+        "2600",
+        "Citibank Europe plc, organizační složka",
+        Some("CITICZPX"),
+        false,
+    ),
+    bank(
+        "2700",
+        "UniCredit Bank Czech Republic and Slovakia, a.s.",
+        Some("BACXCZPP"),
+        true,
+    ),
+    bank("3030", "Air Bank a.s.", Some("AIRACZPP"), true),
+    bank(
+        "3050",
+        "BNP Paribas Personal Finance SA, odštěpný závod",
+        Some("BPPFCZP1"),
+        false,
+    ),
+    bank("3060", "PKO BP S.A., Czech Branch", Some("BPKOCZPP"), false),
+    bank("3500", "ING Bank N.V.", Some("INGBCZPP"), false),
+    bank("4000", "Max banka a.s.", Some("EXPNCZPP"), true),
+    bank(
+        "4300",
+        "Národní rozvojová banka, a.s.",
+        Some("NROZCZPP"),
+        false,
+    ),
+    bank("5500", "Raiffeisenbank a.s.", Some("RZBCCZPP"), true),
+    bank("5800", "J&T BANKA, a.s.", Some("JTBPCZPP"), true),
+    bank("6000", "PPF banka a.s.", Some("PMBPCZPP"), true),
+    bank("6100", "Raiffeisenbank a.s.", Some("EQBKCZPP"), false),
+    bank(
+        "6200",
+        "COMMERZBANK Aktiengesellschaft, pobočka Praha",
+        Some("COBACZPX"),
+        false,
+    ),
+    bank(
+        "6210",
+        "mBank S.A., organizační složka",
+        Some("BREXCZPP"),
+        true,
+    ),
+    bank(
+        "6300",
+        "BNP Paribas S.A., pobočka Česká republika",
+        Some("GEBACZPP"),
+        false,
+    ),
+    bank("6363", "Partners Banka, a.s.", None, true),
+    bank(
+        "6700",
+        "Všeobecná úverová banka a.s., pobočka Praha",
+        Some("SUBACZPP"),
+        false,
+    ),
+    bank(
+        "7910",
+        "Deutsche Bank Aktiengesellschaft Filiale Prag, organizační složka",
+        Some("DEUTCZPX"),
+        false,
+    ),
+    bank("7950", "Raiffeisen stavební spořitelna a.s.", None, false),
+    bank("7960", "ČSOB Stavební spořitelna, a.s.", None, false),
+    bank("7970", "MONETA Stavební Spořitelna, a.s.", None, false),
+    bank(
+        "7990",
+        "Modrá pyramida stavební spořitelna, a.s.",
+        None,
+        false,
+    ),
+    bank(
+        "8030",
+        "Volksbank Raiffeisenbank Nordoberpfalz eG pobočka Cheb",
+        Some("GENODEF1WEV"),
+        false,
+    ),
+    bank(
+        "8040",
+        "Oberbank AG pobočka Česká republika",
+        Some("OBKLCZ2X"),
+        false,
+    ),
+    bank(
+        "8060",
+        "Stavební spořitelna České spořitelny, a.s.",
+        None,
+        false,
+    ),
+    bank(
+        "8090",
+        "Česká exportní banka, a.s.",
+        Some("CZEECZPP"),
+        false,
+    ),
+    bank(
+        "8150",
+        "HSBC Continental Europe, Czech Republic",
+        Some("MIDLCZPP"),
+        false,
+    ),
+    bank("8190", "Sparkasse Oberlausitz-Niederschlesien", None, false),
+    bank(
+        "8198",
+        "FAS finance company s.r.o.",
+        Some("FFCSCZP1"),
+        false,
+    ),
+    bank("8199", "MoneyPolo Europe s.r.o.", Some("MOUSCZP2"), false),
+    bank("8220", "Payment execution s.r.o.", Some("PAERCZP1"), false),
+    bank("8230", "ABAPAY s.r.o.", None, false),
+    bank(
+        "8240",
+        "Družstevní záložna Kredit, v likvidaci",
+        None,
+        false,
+    ),
+    bank(
+        "8250",
+        "Bank of China (CEE) Ltd. Prague Branch",
+        Some("BKCHCZPP"),
+        false,
+    ),
+    bank(
+        "8255",
+        "Bank of Communications Co., Ltd., Prague Branch odštěpný závod",
+        Some("COMMCZPP"),
+        false,
+    ),
+    bank(
+        "8265",
+        "Industrial and Commercial Bank of China Limited, Prague Branch, odštěpný závod",
+        Some("ICBKCZPP"),
+        false,
+    ),
+    bank("8270", "Fairplay Pay s.r.o.", Some("FAPOCZP1"), false),
+    bank("8280", "B-Efekt a.s.", Some("BEFKCZP1"), false),
+    bank("8293", "Mercurius partners s.r.o.", Some("MRPSCZPP"), false),
+    bank("8299", "BESTPAY s.r.o.", Some("BEORCZP2"), false),
+    bank("8500", "Ferratum Bank plc", None, false),
+];
+
+/// All registered banks, sorted by code
+#[must_use]
+pub fn all() -> &'static [Bank] {
+    BANKS
+}
+
+/// Look up a bank by its four digit code
+///
+/// ```
+/// use fiocz_rs::types::bank_code;
+///
+/// let bank = bank_code::lookup("2010").unwrap();
+/// assert_eq!(bank.name, "Fio banka, a.s.");
+/// assert_eq!(bank.bic, Some("FIOBCZPP"));
+/// ```
+#[must_use]
+pub fn lookup(code: &str) -> Option<&'static Bank> {
+    BANKS
+        .binary_search_by(|bank| bank.code.cmp(code))
+        .ok()
+        .map(|i| &BANKS[i])
+}
+
+/// Whether the code is in the bank code list
+#[must_use]
+pub fn is_known(code: &str) -> bool {
+    lookup(code).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_sorted_and_well_formed() {
+        assert!(BANKS.windows(2).all(|w| w[0].code < w[1].code));
+        for bank in BANKS {
+            assert_eq!(bank.code.len(), 4, "{}", bank.code);
+            assert!(bank.code.bytes().all(|b| b.is_ascii_digit()));
+            if bank.bic.is_some() {
+                assert!(bank.bic().is_some(), "{}", bank.code);
+            }
+        }
+    }
+
+    #[test]
+    fn lookups() {
+        assert_eq!(lookup("0800").unwrap().bic, Some("GIBACZPX"));
+        assert!(lookup("0800").unwrap().instant_payments);
+        assert!(lookup("2100").unwrap().bic().is_none());
+        assert!(lookup("9999").is_none());
+        assert!(!is_known("080"));
+        assert!(is_known("2010"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Business identifier codes (ISO 9362)
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// Business identifier code (SWIFT code)
+///
+/// Eight or eleven characters: four letter institution code, two letter
+/// country code, two character location code and an optional three character
+/// branch code.
+///
+/// ```
+/// use fiocz_rs::types::bic::Bic;
+///
+/// let bic: Bic = "FIOBCZPP".parse().unwrap();
+/// assert_eq!(bic.country(), "CZ");
+/// assert_eq!(bic.to_bic11().to_string(), "FIOBCZPPXXX");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Bic(String);
+
+impl Bic {
+    /// Institution code
+    #[must_use]
+    pub fn institution(&self) -> &str {
+        &self.0[..4]
+    }
+
+    /// Country code
+    #[must_use]
+    pub fn country(&self) -> &str {
+        &self.0[4..6]
+    }
+
+    /// Location code
+    #[must_use]
+    pub fn location(&self) -> &str {
+        &self.0[6..8]
+    }
+
+    /// Branch code, `None` for eight character codes
+    #[must_use]
+    pub fn branch(&self) -> Option<&str> {
+        self.0.get(8..).filter(|branch| !branch.is_empty())
+    }
+
+    /// Eleven character form, using `XXX` for the primary office
+    #[must_use]
+    pub fn to_bic11(&self) -> Self {
+        if self.0.len() == 11 {
+            self.clone()
+        } else {
+            Self(format!("{}XXX", self.0))
+        }
+    }
+
+    /// Whether two codes identify the same office (`FIOBCZPP` == `FIOBCZPPXXX`)
+    #[must_use]
+    pub fn same_office(&self, other: &Self) -> bool {
+        self.to_bic11() == other.to_bic11()
+    }
+}
+
+impl fmt::Display for Bic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Bic {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bic = s.trim().to_ascii_uppercase();
+        let invalid = |reason: &str| Error::InvalidBic(format!("{s}: {reason}"));
+        if bic.len() != 8 && bic.len() != 11 {
+            return Err(invalid("must have 8 or 11 characters"));
+        }
+        if !bic.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(invalid("invalid characters"));
+        }
+        if !bic[..6].bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(invalid("institution and country codes must be letters"));
+        }
+        Ok(Self(bic))
+    }
+}
+
+impl TryFrom<String> for Bic {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Bic> for String {
+    fn from(value: Bic) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_parts() {
+        let bic: Bic = "gibaczpx".parse().unwrap();
+        assert_eq!(bic.to_string(), "GIBACZPX");
+        assert_eq!(bic.institution(), "GIBA");
+        assert_eq!(bic.country(), "CZ");
+        assert_eq!(bic.location(), "PX");
+        assert_eq!(bic.branch(), None);
+        let bic: Bic = "FIOBCZPPXXX".parse().unwrap();
+        assert_eq!(bic.branch(), Some("XXX"));
+    }
+
+    #[test]
+    fn same_office() {
+        let short: Bic = "FIOBCZPP".parse().unwrap();
+        let long: Bic = "FIOBCZPPXXX".parse().unwrap();
+        assert!(short.same_office(&long));
+        assert_ne!(short, long);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        for input in ["FIOBCZP", "FIOBCZPPXX", "F1OBCZPP", "FIOB-ZPP", ""] {
+            assert!(
+                matches!(input.parse::<Bic>(), Err(Error::InvalidBic(_))),
+                "{input}"
+            );
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! Currencies (ISO 4217) and amounts of money
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+macro_rules! currencies {
+    ($($variant:ident => $code:literal, $minor:literal;)*) => {
+        /// Currency identified by its ISO 4217 alphabetic code
+        ///
+        /// Codes missing from the catalogue parse as [`Currency::Other`], so a
+        /// statement in a new currency still deserialises. Payment orders
+        /// reject them.
+        ///
+        /// ```
+        /// use fiocz_rs::types::currency::Currency;
+        ///
+        /// let currency: Currency = "JPY".parse().unwrap();
+        /// assert_eq!(currency.minor_units(), 0);
+        /// assert_eq!(Currency::Czk.to_string(), "CZK");
+        ///
+        /// let other: Currency = "xts".parse().unwrap();
+        /// assert_eq!(other.code(), "XTS");
+        /// assert!(!other.is_known());
+        /// ```
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub enum Currency {
+            $(
+                #[doc = $code]
+                $variant,
+            )*
+            /// Code not in the catalogue, obtained by parsing
+            Other(OtherCode),
+        }
+
+        const ALL: &[Currency] = &[$(Currency::$variant),*];
+
+        impl Currency {
+            /// Three letter ISO 4217 code
+            #[must_use]
+            pub fn code(&self) -> &str {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Other(code) => code.as_str(),
+                }
+            }
+
+            /// Number of decimal places of the minor unit
+            ///
+            /// Codes without a minor unit in ISO 4217 (precious metals, XDR)
+            /// use 4; codes not in the catalogue are assumed to use 2.
+            #[must_use]
+            pub const fn minor_units(self) -> u32 {
+                match self {
+                    $(Self::$variant => $minor,)*
+                    Self::Other(_) => 2,
+                }
+            }
+        }
+    };
+}
+
+/// Three letter code missing from the currency catalogue
+///
+/// Only created by parsing a [`Currency`], so it is always three uppercase
+/// ASCII letters and never a catalogued code: every currency has exactly one
+/// representation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OtherCode([u8; 3]);
+
+impl OtherCode {
+    /// The code
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).expect("currency code is ASCII")
+    }
+}
+
+impl fmt::Display for OtherCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+currencies! {
+    Aed => "AED", 2;
+    Afn => "AFN", 2;
+    All => "ALL", 2;
+    Amd => "AMD", 2;
+    Ang => "ANG", 2;
+    Aoa => "AOA", 2;
+    Ars => "ARS", 2;
+    Aud => "AUD", 2;
+    Awg => "AWG", 2;
+    Azn => "AZN", 2;
+    Bam => "BAM", 2;
+    Bbd => "BBD", 2;
+    Bdt => "BDT", 2;
+    Bgn => "BGN", 2;
+    Bhd => "BHD", 3;
+    Bif => "BIF", 0;
+    Bmd => "BMD", 2;
+    Bnd => "BND", 2;
+    Bob => "BOB", 2;
+    Bov => "BOV", 2;
+    Brl => "BRL", 2;
+    Bsd => "BSD", 2;
+    Btn => "BTN", 2;
+    Bwp => "BWP", 2;
+    Byn => "BYN", 2;
+    Bzd => "BZD", 2;
+    Cad => "CAD", 2;
+    Cdf => "CDF", 2;
+    Che => "CHE", 2;
+    Chf => "CHF", 2;
+    Chw => "CHW", 2;
+    Clf => "CLF", 4;
+    Clp => "CLP", 0;
+    Cny => "CNY", 2;
+    Cop => "COP", 2;
+    Cou => "COU", 2;
+    Crc => "CRC", 2;
+    Cuc => "CUC", 2;
+    Cup => "CUP", 2;
+    Cve => "CVE", 2;
+    Czk => "CZK", 2;
+    Djf => "DJF", 0;
+    Dkk => "DKK", 2;
+    Dop => "DOP", 2;
+    Dzd => "DZD", 2;
+    Egp => "EGP", 2;
+    Ern => "ERN", 2;
+    Etb => "ETB", 2;
+    Eur => "EUR", 2;
+    Fjd => "FJD", 2;
+    Fkp => "FKP", 2;
+    Gbp => "GBP", 2;
+    Gel => "GEL", 2;
+    Ghs => "GHS", 2;
+    Gip => "GIP", 2;
+    Gmd => "GMD", 2;
+    Gnf => "GNF", 0;
+    Gtq => "GTQ", 2;
+    Gyd => "GYD", 2;
+    Hkd => "HKD", 2;
+    Hnl => "HNL", 2;
+    Hrk => "HRK", 2;
+    Htg => "HTG", 2;
+    Huf => "HUF", 2;
+    Idr => "IDR", 2;
+    Ils => "ILS", 2;
+    Inr => "INR", 2;
+    Iqd => "IQD", 3;
+    Irr => "IRR", 2;
+    Isk => "ISK", 0;
+    Jmd => "JMD", 2;
+    Jod => "JOD", 3;
+    Jpy => "JPY", 0;
+    Kes => "KES", 2;
+    Kgs => "KGS", 2;
+    Khr => "KHR", 2;
+    Kmf => "KMF", 0;
+    Kpw => "KPW", 2;
+    Krw => "KRW", 0;
+    Kwd => "KWD", 3;
+    Kyd => "KYD", 2;
+    Kzt => "KZT", 2;
+    Lak => "LAK", 2;
+    Lbp => "LBP", 2;
+    Lkr => "LKR", 2;
+    Lrd => "LRD", 2;
+    Lsl => "LSL", 2;
+    Lyd => "LYD", 3;
+    Mad => "MAD", 2;
+    Mdl => "MDL", 2;
+    Mga => "MGA", 2;
+    Mkd => "MKD", 2;
+    Mmk => "MMK", 2;
+    Mnt => "MNT", 2;
+    Mop => "MOP", 2;
+    Mru => "MRU", 2;
+    Mur => "MUR", 2;
+    Mvr => "MVR", 2;
+    Mwk => "MWK", 2;
+    Mxn => "MXN", 2;
+    Mxv => "MXV", 2;
+    Myr => "MYR", 2;
+    Mzn => "MZN", 2;
+    Nad => "NAD", 2;
+    Ngn => "NGN", 2;
+    Nio => "NIO", 2;
+    Nok => "NOK", 2;
+    Npr => "NPR", 2;
+    Nzd => "NZD", 2;
+    Omr => "OMR", 3;
+    Pab => "PAB", 2;
+    Pen => "PEN", 2;
+    Pgk => "PGK", 2;
+    Php => "PHP", 2;
+    Pkr => "PKR", 2;
+    Pln => "PLN", 2;
+    Pyg => "PYG", 0;
+    Qar => "QAR", 2;
+    Ron => "RON", 2;
+    Rsd => "RSD", 2;
+    Rub => "RUB", 2;
+    Rwf => "RWF", 0;
+    Sar => "SAR", 2;
+    Sbd => "SBD", 2;
+    Scr => "SCR", 2;
+    Sdg => "SDG", 2;
+    Sek => "SEK", 2;
+    Sgd => "SGD", 2;
+    Shp => "SHP", 2;
+    Sle => "SLE", 2;
+    Sll => "SLL", 2;
+    Sos => "SOS", 2;
+    Srd => "SRD", 2;
+    Ssp => "SSP", 2;
+    Stn => "STN", 2;
+    Svc => "SVC", 2;
+    Syp => "SYP", 2;
+    Szl => "SZL", 2;
+    Thb => "THB", 2;
+    Tjs => "TJS", 2;
+    Tmt => "TMT", 2;
+    Tnd => "TND", 3;
+    Top => "TOP", 2;
+    Try => "TRY", 2;
+    Ttd => "TTD", 2;
+    Twd => "TWD", 2;
+    Tzs => "TZS", 2;
+    Uah => "UAH", 2;
+    Ugx => "UGX", 0;
+    Usd => "USD", 2;
+    Usn => "USN", 2;
+    Uyi => "UYI", 0;
+    Uyu => "UYU", 2;
+    Uyw => "UYW", 4;
+    Uzs => "UZS", 2;
+    Ved => "VED", 2;
+    Ves => "VES", 2;
+    Vnd => "VND", 0;
+    Vuv => "VUV", 0;
+    Wst => "WST", 2;
+    Xaf => "XAF", 0;
+    Xag => "XAG", 4;
+    Xau => "XAU", 4;
+    Xcd => "XCD", 2;
+    Xcg => "XCG", 2;
+    Xdr => "XDR", 4;
+    Xof => "XOF", 0;
+    Xpd => "XPD", 4;
+    Xpf => "XPF", 0;
+    Xpt => "XPT", 4;
+    Yer => "YER", 2;
+    Zar => "ZAR", 2;
+    Zmw => "ZMW", 2;
+    Zwg => "ZWG", 2;
+    Zwl => "ZWL", 2;
+}
+
+impl Currency {
+    /// All catalogued currencies, sorted by code
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        ALL
+    }
+
+    /// Whether the currency is in the catalogue
+    #[must_use]
+    pub const fn is_known(self) -> bool {
+        !matches!(self, Self::Other(_))
+    }
+
+    /// Round an amount to the currency's minor unit, halves away from zero
+    #[must_use]
+    pub fn round(self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.minor_units(), RoundingStrategy::MidpointAwayFromZero)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        if let Ok(i) = ALL.binary_search_by(|c| c.code().cmp(code.as_str())) {
+            return Ok(ALL[i]);
+        }
+        match code.as_bytes() {
+            &[a, b, c] if code.bytes().all(|b| b.is_ascii_uppercase()) => {
+                Ok(Self::Other(OtherCode([a, b, c])))
+            }
+            _ => Err(Error::InvalidCurrency(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.code().to_string()
+    }
+}
+
+/// Amount of money in a currency, rounded to the currency's minor unit
+///
+/// ```
+/// use fiocz_rs::types::currency::{Currency, Money};
+/// use rust_decimal::Decimal;
+///
+/// let money = Money::new(Decimal::new(10005, 3), Currency::Czk);
+/// assert_eq!(money.to_string(), "10.01 CZK");
+/// assert!(Money::exact(Decimal::new(10005, 3), Currency::Czk).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "MoneyFields")]
+pub struct Money {
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// Deserialized money before rounding
+#[derive(Deserialize)]
+struct MoneyFields {
+    amount: Decimal,
+    currency: Currency,
+}
+
+impl From<MoneyFields> for Money {
+    fn from(value: MoneyFields) -> Self {
+        Self::new(value.amount, value.currency)
+    }
+}
+
+impl Money {
+    /// Create money, rounding the amount to the currency's minor unit
+    #[must_use]
+    pub fn new(amount: Decimal, currency: Currency) -> Self {
+        Self {
+            amount: currency.round(amount),
+            currency,
+        }
+    }
+
+    /// Create money from an amount that must not need rounding
+    /// # Errors
+    /// * `Error::InvalidAmount` - The amount has more decimal places than the currency allows
+    pub fn exact(amount: Decimal, currency: Currency) -> Result<Self, Error> {
+        let money = Self::new(amount, currency);
+        if money.amount == amount {
+            Ok(money)
+        } else {
+            Err(Error::InvalidAmount(format!(
+                "{amount} {currency} has more than {} decimal places",
+                currency.minor_units()
+            )))
+        }
+    }
+
+    /// Amount
+    #[must_use]
+    pub const fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Currency
+    #[must_use]
+    pub const fn currency(&self) -> Currency {
+        self.currency
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut amount = self.amount;
+        amount.rescale(self.currency.minor_units());
+        write!(f, "{amount} {}", self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_codes() {
+        assert_eq!("czk".parse::<Currency>().unwrap(), Currency::Czk);
+        assert_eq!(" EUR ".parse::<Currency>().unwrap(), Currency::Eur);
+        assert_eq!("bgn".parse::<Currency>().unwrap(), Currency::Bgn);
+        assert_eq!("XCG".parse::<Currency>().unwrap(), Currency::Xcg);
+        let other = "xyz".parse::<Currency>().unwrap();
+        assert_eq!(other, Currency::Other(OtherCode(*b"XYZ")));
+        assert_eq!(other.to_string(), "XYZ");
+        assert!(!other.is_known());
+        for invalid in ["XY", "EURO", "X1Z", ""] {
+            assert!(matches!(
+                invalid.parse::<Currency>(),
+                Err(Error::InvalidCurrency(_))
+            ));
+        }
+        assert!(ALL.windows(2).all(|w| w[0].code() < w[1].code()));
+        for currency in Currency::all() {
+            assert_eq!(currency.code().parse::<Currency>().unwrap(), *currency);
+        }
+    }
+
+    #[test]
+    fn minor_units() {
+        assert_eq!(Currency::Czk.minor_units(), 2);
+        assert_eq!(Currency::Jpy.minor_units(), 0);
+        assert_eq!(Currency::Kwd.minor_units(), 3);
+    }
+
+    #[test]
+    fn money_rounding() {
+        let money = Money::new(Decimal::new(-1235, 1), Currency::Jpy);
+        assert_eq!(money.amount(), Decimal::new(-124, 0));
+        assert_eq!(money.to_string(), "-124 JPY");
+        assert_eq!(
+            Money::new(Decimal::new(5, 0), Currency::Kwd).to_string(),
+            "5.000 KWD"
+        );
+        assert!(Money::exact(Decimal::new(1050, 2), Currency::Eur).is_ok());
+        assert!(matches!(
+            Money::exact(Decimal::new(1, 1), Currency::Jpy),
+            Err(Error::InvalidAmount(_))
+        ));
+    }
+
+    #[test]
+    fn serde() {
+        assert_eq!(serde_json::to_string(&Currency::Usd).unwrap(), "\"USD\"");
+        let money: Money =
+            serde_json::from_str(r#"{"amount": 12.499, "currency": "EUR"}"#).unwrap();
+        assert_eq!(money.to_string(), "12.50 EUR");
+        assert!(serde_json::from_str::<Currency>("\"EURO\"").is_err());
+        let other: Currency = serde_json::from_str("\"ZZZ\"").unwrap();
+        assert_eq!(serde_json::to_string(&other).unwrap(), "\"ZZZ\"");
+    }
+}
@@ -0,0 +1,336 @@
+//! International bank account numbers (ISO 13616)
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::account_number::CzAccountNumber;
+use crate::types::bank_code::Bank;
+use crate::types::bic::Bic;
+
+/// IBAN lengths per country from the ISO 13616 registry
+const COUNTRY_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AE", 23),
+    ("AL", 28),
+    ("AT", 20),
+    ("AZ", 28),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BI", 27),
+    ("BR", 29),
+    ("BY", 28),
+    ("CH", 21),
+    ("CR", 22),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DJ", 27),
+    ("DK", 18),
+    ("DO", 28),
+    ("EE", 20),
+    ("EG", 29),
+    ("ES", 24),
+    ("FI", 18),
+    ("FK", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GE", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("GT", 28),
+    ("HN", 28),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IQ", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("JO", 30),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LC", 32),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("LY", 25),
+    ("MC", 27),
+    ("MD", 24),
+    ("ME", 22),
+    ("MK", 19),
+    ("MN", 20),
+    ("MR", 27),
+    ("MT", 31),
+    ("MU", 30),
+    ("NI", 28),
+    ("NL", 18),
+    ("NO", 15),
+    ("OM", 23),
+    ("PK", 24),
+    ("PL", 28),
+    ("PS", 29),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("RU", 33),
+    ("SA", 24),
+    ("SC", 31),
+    ("SD", 18),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("SO", 23),
+    ("ST", 25),
+    ("SV", 28),
+    ("TL", 23),
+    ("TN", 24),
+    ("TR", 26),
+    ("UA", 29),
+    ("VA", 22),
+    ("VG", 24),
+    ("XK", 20),
+    ("YE", 30),
+];
+
+/// International bank account number
+///
+/// Parsing accepts the electronic (`CZ6508000000192000145399`) and the
+/// printed, space separated form, validates the country specific length and
+/// the mod 97 check digits, and stores the electronic form.
+///
+/// ```
+/// use fiocz_rs::types::iban::Iban;
+///
+/// let iban: Iban = "CZ65 0800 0000 1920 0014 5399".parse().unwrap();
+/// assert_eq!(iban.to_string(), "CZ6508000000192000145399");
+/// assert_eq!(iban.pretty(), "CZ65 0800 0000 1920 0014 5399");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Iban(String);
+
+impl Iban {
+    /// Two letter country code
+    #[must_use]
+    pub fn country(&self) -> &str {
+        &self.0[..2]
+    }
+
+    /// Check digits
+    #[must_use]
+    pub fn check_digits(&self) -> &str {
+        &self.0[2..4]
+    }
+
+    /// Country specific part (BBAN)
+    #[must_use]
+    pub fn bban(&self) -> &str {
+        &self.0[4..]
+    }
+
+    /// Electronic form without spaces
+    #[must_use]
+    pub fn electronic(&self) -> &str {
+        &self.0
+    }
+
+    /// Printed form in groups of four characters
+    #[must_use]
+    pub fn pretty(&self) -> String {
+        self.0
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| String::from_utf8_lossy(chunk))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Build an IBAN from country code and BBAN, computing the check digits
+    /// # Errors
+    /// * `Error::InvalidIban` - Unknown country or wrong length
+    pub fn from_bban(country: &str, bban: &str) -> Result<Self, Error> {
+        let remainder = mod97(&format!("{bban}{country}00"))
+            .ok_or_else(|| Error::InvalidIban(format!("{country}{bban}")))?;
+        format!("{country}{:02}{bban}", 98 - remainder).parse()
+    }
+
+    /// Convert a Czech IBAN to the domestic account number
+    /// # Errors
+    /// * `Error::InvalidIban` - Not a Czech IBAN
+    /// * `Error::InvalidAccountNumber` - The embedded account fails the mod 11 check
+    pub fn to_cz_account(&self) -> Result<CzAccountNumber, Error> {
+        if self.country() != "CZ" {
+            return Err(Error::InvalidIban(format!("{self} is not a Czech IBAN")));
+        }
+        let bban = self.bban();
+        let prefix = bban[4..10]
+            .parse()
+            .map_err(|_| Error::InvalidIban(self.to_string()))?;
+        let number = bban[10..]
+            .parse()
+            .map_err(|_| Error::InvalidIban(self.to_string()))?;
+        CzAccountNumber::new(prefix, number, &bban[..4])
+    }
+}
+
+impl CzAccountNumber {
+    /// Czech IBAN of the account
+    #[must_use]
+    pub fn to_iban(&self) -> Iban {
+        let bban = format!(
+            "{}{:06}{:010}",
+            self.bank_code(),
+            self.prefix().unwrap_or(0),
+            self.number()
+        );
+        Iban::from_bban("CZ", &bban).expect("valid account number yields valid IBAN")
+    }
+
+    /// BIC of the account's bank from the bundled bank code list
+    #[must_use]
+    pub fn bic(&self) -> Option<Bic> {
+        self.bank().and_then(Bank::bic)
+    }
+}
+
+impl fmt::Display for Iban {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Iban {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let iban: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_ascii_uppercase();
+        let invalid = |reason: &str| Error::InvalidIban(format!("{s}: {reason}"));
+        if !iban.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(invalid("invalid characters"));
+        }
+        let country = iban.get(..2).ok_or_else(|| invalid("too short"))?;
+        let length = COUNTRY_LENGTHS
+            .iter()
+            .find(|(code, _)| *code == country)
+            .map(|(_, length)| *length)
+            .ok_or_else(|| invalid("unknown country"))?;
+        if iban.len() != length {
+            return Err(invalid(&format!(
+                "{country} IBAN must have {length} characters"
+            )));
+        }
+        if !iban[2..4].bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid("check digits are not numeric"));
+        }
+        let rearranged = format!("{}{}", &iban[4..], &iban[..4]);
+        if mod97(&rearranged) != Some(1) {
+            return Err(invalid("check digits do not match"));
+        }
+        Ok(Self(iban))
+    }
+}
+
+impl TryFrom<String> for Iban {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Iban> for String {
+    fn from(value: Iban) -> Self {
+        value.0
+    }
+}
+
+/// Remainder of the number formed by replacing letters with 10..=35
+fn mod97(value: &str) -> Option<u32> {
+    let mut remainder = 0u32;
+    for c in value.chars() {
+        let digit = c.to_digit(36)?;
+        remainder = if digit < 10 {
+            (remainder * 10 + digit) % 97
+        } else {
+            (remainder * 100 + digit) % 97
+        };
+    }
+    Some(remainder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_electronic_and_pretty() {
+        let a: Iban = "CZ6508000000192000145399".parse().unwrap();
+        let b: Iban = "cz65 0800 0000 1920 0014 5399".parse().unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.country(), "CZ");
+        assert_eq!(a.check_digits(), "65");
+        assert_eq!(a.bban(), "08000000192000145399");
+        assert_eq!(a.pretty(), "CZ65 0800 0000 1920 0014 5399");
+    }
+
+    #[test]
+    fn parse_other_countries() {
+        assert!("DE89370400440532013000".parse::<Iban>().is_ok());
+        assert!("GB29 NWBK 6016 1331 9268 19".parse::<Iban>().is_ok());
+        assert!("SK3112000000198742637541".parse::<Iban>().is_ok());
+    }
+
+    #[test]
+    fn reject_invalid() {
+        for input in [
+            "CZ6608000000192000145399",
+            "CZ650800000019200014539",
+            "XX6508000000192000145399",
+            "CZ65-0800-0000-1920-0014-5399",
+            "C",
+            "",
+        ] {
+            assert!(
+                matches!(input.parse::<Iban>(), Err(Error::InvalidIban(_))),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn cz_account_round_trip() {
+        let account: CzAccountNumber = "19-2000145399/0800".parse().unwrap();
+        let iban = account.to_iban();
+        assert_eq!(iban.to_string(), "CZ6508000000192000145399");
+        assert_eq!(iban.to_cz_account().unwrap(), account);
+        assert_eq!(account.bic().unwrap().to_string(), "GIBACZPX");
+    }
+
+    #[test]
+    fn non_czech_to_account_fails() {
+        let iban: Iban = "DE89370400440532013000".parse().unwrap();
+        assert!(iban.to_cz_account().is_err());
+    }
+
+    #[test]
+    fn serde_as_string() {
+        let iban: Iban = "CZ6508000000192000145399".parse().unwrap();
+        let json = serde_json::to_string(&iban).unwrap();
+        assert_eq!(json, "\"CZ6508000000192000145399\"");
+        assert_eq!(serde_json::from_str::<Iban>(&json).unwrap(), iban);
+        assert!(serde_json::from_str::<Iban>("\"CZ00\"").is_err());
+    }
+}
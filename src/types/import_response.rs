@@ -1,6 +1,8 @@
 //! Import response types
 use rust_decimal::Decimal;
 
+use crate::error::Error;
+
 /// Response from the import endpoint
 ///
 /// The FIO API returns an XML response after importing transactions.
@@ -18,4 +20,202 @@ pub struct ImportResponse {
     pub sum_debet: Option<Decimal>,
     /// Sum of credit items in the batch
     pub sum_credit: Option<Decimal>,
+    /// Messages about individual orders
+    pub details: Vec<OrderDetail>,
+}
+
+/// Messages about one order of an import
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderDetail {
+    /// Position of the order in the imported file, starting at 1
+    pub id: usize,
+    /// Messages for the order
+    pub messages: Vec<OrderMessage>,
+}
+
+/// Message about an order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderMessage {
+    /// Status (ok, error, warning)
+    pub status: String,
+    /// Error code
+    pub error_code: Option<i32>,
+    /// Message text
+    pub text: String,
+}
+
+impl ImportResponse {
+    /// Parse the XML returned by the import endpoint
+    ///
+    /// ```
+    /// use fiocz_rs::types::import_response::ImportResponse;
+    ///
+    /// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+    /// <responseImport><result><errorCode>0</errorCode>
+    /// <idInstruction>105623</idInstruction><status>ok</status>
+    /// <sums><sum id="CZK"><sumCredit>0</sumCredit><sumDebet>400.00</sumDebet></sum></sums>
+    /// </result></responseImport>"#;
+    /// let response = ImportResponse::from_xml(xml).unwrap();
+    /// assert!(response.is_ok());
+    /// assert_eq!(response.id_instruction.as_deref(), Some("105623"));
+    /// ```
+    /// # Errors
+    /// * `Error::InvalidResponse` - The response has no valid `errorCode`
+    pub fn from_xml(xml: &str) -> Result<Self, Error> {
+        let result = elements(xml, "result")
+            .into_iter()
+            .next()
+            .map_or(xml, |(_, inner)| inner);
+        let text = |tag: &str| {
+            elements(result, tag)
+                .into_iter()
+                .next()
+                .map(|(_, inner)| unescape(inner.trim()))
+        };
+        let decimal = |tag: &str| text(tag).and_then(|v| v.parse::<Decimal>().ok());
+        let error_code = text("errorCode")
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| Error::InvalidResponse(format!("Missing import error code: {xml}")))?;
+        let details = elements(xml, "detail")
+            .into_iter()
+            .filter_map(|(attributes, inner)| {
+                Some(OrderDetail {
+                    id: attribute(attributes, "id")?.parse().ok()?,
+                    messages: elements(inner, "message")
+                        .into_iter()
+                        .map(|(attributes, text)| OrderMessage {
+                            status: attribute(attributes, "status").unwrap_or_default(),
+                            error_code: attribute(attributes, "errorCode")
+                                .and_then(|code| code.parse().ok()),
+                            text: unescape(text.trim()),
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+        Ok(Self {
+            error_code,
+            id_instruction: text("idInstruction").filter(|id| !id.is_empty()),
+            status: text("status"),
+            sum_debet: decimal("sumDebet"),
+            sum_credit: decimal("sumCredit"),
+            details,
+        })
+    }
+
+    /// Whether the bank accepted the import, possibly with warnings
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        matches!(self.error_code, 0 | 2)
+    }
+
+    /// Messages about the order at a position of the imported file
+    #[must_use]
+    pub fn messages(&self, id: usize) -> &[OrderMessage] {
+        self.details
+            .iter()
+            .find(|d| d.id == id)
+            .map_or(&[], |d| &d.messages)
+    }
+}
+
+/// Attributes and content of all `<tag ...>content</tag>` elements
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<(&'a str, &'a str)> {
+    let open = format!("<{tag}");
+    let close = format!("</{tag}>");
+    let mut found = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find('>') else { break };
+        let attributes = &after[..end];
+        if !attributes.is_empty() && !attributes.starts_with(char::is_whitespace) {
+            // A longer tag sharing the prefix, e.g. <details> for <detail>
+            rest = after;
+            continue;
+        }
+        if let Some(attributes) = attributes.strip_suffix('/') {
+            found.push((attributes, ""));
+            rest = &after[end + 1..];
+            continue;
+        }
+        let content = &after[end + 1..];
+        let Some(len) = content.find(&close) else {
+            break;
+        };
+        found.push((attributes, &content[..len]));
+        rest = &content[len + close.len()..];
+    }
+    found
+}
+
+/// Value of an attribute in the attribute list of an element
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let pattern = format!("{name}=\"");
+    let mut rest = attributes;
+    while let Some(start) = rest.find(&pattern) {
+        let preceded_by_space = start == 0 || rest[..start].ends_with(char::is_whitespace);
+        let value = &rest[start + pattern.len()..];
+        if preceded_by_space {
+            return value.find('"').map(|end| unescape(&value[..end]));
+        }
+        rest = value;
+    }
+    None
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REJECTED: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<responseImport xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <result>
+    <errorCode>1</errorCode>
+    <status>error</status>
+    <sums><sum id="CZK"><sumCredit>0</sumCredit><sumDebet>1500.50</sumDebet></sum></sums>
+  </result>
+  <ordersDetails>
+    <detail id="2">
+      <messages>
+        <message status="error" errorCode="1016">Neplatné &quot;accountTo&quot; &amp; bankCode</message>
+        <message status="warning" errorCode="21">Datum v minulosti</message>
+      </messages>
+    </detail>
+  </ordersDetails>
+</responseImport>"#;
+
+    #[test]
+    fn parse_rejected_import() {
+        let response = ImportResponse::from_xml(REJECTED).unwrap();
+        assert_eq!(response.error_code, 1);
+        assert!(!response.is_ok());
+        assert_eq!(response.id_instruction, None);
+        assert_eq!(response.status.as_deref(), Some("error"));
+        assert_eq!(response.sum_debet, Some(Decimal::new(150_050, 2)));
+        assert_eq!(response.details.len(), 1);
+        let messages = response.messages(2);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].status, "error");
+        assert_eq!(messages[0].error_code, Some(1016));
+        assert!(messages[0].text.contains("\"accountTo\" & bankCode"));
+        assert!(response.messages(1).is_empty());
+    }
+
+    #[test]
+    fn missing_error_code() {
+        assert!(matches!(
+            ImportResponse::from_xml("<responseImport/>"),
+            Err(Error::InvalidResponse(_))
+        ));
+    }
 }
@@ -2,6 +2,10 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
+use crate::types::bic::Bic;
+use crate::types::currency::{Currency, Money};
+use crate::types::iban::Iban;
+
 /// Merchant statement response wrapper
 ///
 /// Returned by the merchant card transactions endpoint for POS terminal
@@ -24,11 +28,13 @@ pub struct MerchantInfo {
     /// Bank ID (4 digit BBAN code)
     pub bank_id: String,
     /// Currency (ISO 4217)
-    pub currency: String,
-    /// IBAN
-    pub iban: String,
-    /// BIC (ISO 9362)
-    pub bic: String,
+    pub currency: Currency,
+    /// IBAN, `None` when missing or invalid
+    #[serde(default, deserialize_with = "crate::types::lenient")]
+    pub iban: Option<Iban>,
+    /// BIC (ISO 9362), `None` when missing or invalid
+    #[serde(default, deserialize_with = "crate::types::lenient")]
+    pub bic: Option<Bic>,
     /// Start of selected period
     pub date_start: String,
     /// End of selected period
@@ -81,7 +87,7 @@ pub struct MerchantTransaction {
     pub transaction_amount: Option<Decimal>,
     /// Transaction currency (ISO 4217)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub transaction_currency: Option<String>,
+    pub transaction_currency: Option<Currency>,
     /// Transaction type (ON_US = Fio card, DOMESTIC = Czech card, FOREIGN = foreign card)
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
@@ -114,3 +120,14 @@ pub struct MerchantTransaction {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub vs: Option<String>,
 }
+
+impl MerchantTransaction {
+    /// Individual transaction amount in the transaction currency
+    #[must_use]
+    pub fn transaction_money(&self) -> Option<Money> {
+        Some(Money::new(
+            self.transaction_amount?,
+            self.transaction_currency?,
+        ))
+    }
+}
@@ -1,10 +1,41 @@
 //! This module contains all the types used in the application.
+pub mod account_number;
 pub mod account_statement;
+pub mod bank_code;
+pub mod bic;
+pub mod currency;
+pub mod iban;
 pub mod import_response;
 pub mod merchant;
+pub mod movement;
+pub mod payment_reason;
+pub mod symbol;
 pub mod transaction;
 
+use log::warn;
+use serde::{Deserialize, Deserializer};
 use std::fmt;
+use std::str::FromStr;
+
+/// Deserialise an optional value, turning one that does not parse into `None`
+///
+/// Used for informational fields of bank responses, so one odd value does
+/// not make the whole statement unreadable.
+pub(crate) fn lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let raw = Option::<String>::deserialize(deserializer)?;
+    Ok(raw.and_then(|raw| match raw.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Ignoring unparseable value {raw:?}: {e}");
+            None
+        }
+    }))
+}
 
 /// Supported export data formats
 ///
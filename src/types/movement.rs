@@ -0,0 +1,310 @@
+//! Typed account movement
+use log::warn;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::account_statement::{TransactionData, TransactionDataEnum};
+use crate::types::bank_code;
+use crate::types::currency::{Currency, Money};
+use crate::types::payment_reason::PaymentReason;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+
+/// Raw movement as returned by the API, keyed by column name (`column0` … `column27`)
+pub type RawMovement = HashMap<String, Option<TransactionData>>;
+
+/// Account movement with the documented columns extracted
+///
+/// Column mapping follows the FIO API documentation, e.g. `column22` is the
+/// movement ID and `column1` the amount.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Movement {
+    /// Movement ID (`column22`)
+    pub id: i64,
+    /// Date of the movement (`column0`), e.g. `2024-01-15+0100`
+    pub date: String,
+    /// Amount, negative for outgoing movements (`column1`)
+    pub amount: Decimal,
+    /// Currency (`column14`)
+    pub currency: Currency,
+    /// Counter account number (`column2`)
+    pub counter_account: Option<String>,
+    /// Counter account name (`column10`)
+    pub counter_account_name: Option<String>,
+    /// Counter account bank code (`column3`)
+    pub bank_code: Option<String>,
+    /// Counter account bank name (`column12`)
+    pub bank_name: Option<String>,
+    /// Constant symbol (`column4`)
+    pub ks: Option<ConstantSymbol>,
+    /// Variable symbol (`column5`)
+    pub vs: Option<VariableSymbol>,
+    /// Specific symbol (`column6`)
+    pub ss: Option<SpecificSymbol>,
+    /// User identification (`column7`)
+    pub user_identification: Option<String>,
+    /// Message for recipient (`column16`)
+    pub message_for_recipient: Option<String>,
+    /// Movement type (`column8`)
+    pub movement_type: Option<String>,
+    /// Executed by (`column9`)
+    pub executed_by: Option<String>,
+    /// Specification, e.g. original amount of a foreign payment (`column18`)
+    pub specification: Option<String>,
+    /// Comment (`column25`)
+    pub comment: Option<String>,
+    /// Counter account BIC (`column26`)
+    pub bic: Option<String>,
+    /// Instruction ID (`column17`)
+    pub instruction_id: Option<i64>,
+    /// Payer reference (`column27`)
+    pub payer_reference: Option<String>,
+    /// Payment reason of foreign and euro payments, found by its column
+    /// name `Platební titul` as it has no documented column number
+    #[serde(default)]
+    pub payment_reason: Option<PaymentReason>,
+}
+
+impl Movement {
+    /// Parse a movement from the raw column map
+    /// # Arguments
+    /// * `raw` - Movement as returned in `TransactionList`
+    /// # Errors
+    /// * `Error::InvalidResponse` - Movement ID, date, amount or currency is missing
+    pub fn from_raw(raw: &RawMovement) -> Result<Self, Error> {
+        let id = integer(raw, "column22")
+            .ok_or_else(|| Error::InvalidResponse("Movement without ID".to_string()))?;
+        let date = string(raw, "column0")
+            .ok_or_else(|| Error::InvalidResponse(format!("Movement {id} has no date")))?;
+        let amount = decimal(raw, "column1")
+            .ok_or_else(|| Error::InvalidResponse(format!("Movement {id} has no amount")))?;
+        let currency = string(raw, "column14")
+            .ok_or_else(|| Error::InvalidResponse(format!("Movement {id} has no currency")))?
+            .parse()
+            .map_err(|e| Error::InvalidResponse(format!("Movement {id}: {e}")))?;
+        Ok(Self {
+            id,
+            date,
+            amount,
+            currency,
+            counter_account: string(raw, "column2"),
+            counter_account_name: string(raw, "column10"),
+            bank_code: string(raw, "column3"),
+            bank_name: string(raw, "column12"),
+            ks: symbol(raw, "column4"),
+            vs: symbol(raw, "column5"),
+            ss: symbol(raw, "column6"),
+            user_identification: string(raw, "column7"),
+            message_for_recipient: string(raw, "column16"),
+            movement_type: string(raw, "column8"),
+            executed_by: string(raw, "column9"),
+            specification: string(raw, "column18"),
+            comment: string(raw, "column25"),
+            bic: string(raw, "column26"),
+            instruction_id: integer(raw, "column17"),
+            payer_reference: string(raw, "column27"),
+            payment_reason: payment_reason(raw),
+        })
+    }
+
+    /// Date of the movement without the timezone suffix (`YYYY-MM-DD`)
+    #[must_use]
+    pub fn day(&self) -> &str {
+        self.date.get(..10).unwrap_or(&self.date)
+    }
+
+    /// Amount in the movement currency
+    #[must_use]
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+
+    /// Counter account bank name, falling back to the bundled bank code list
+    /// when the API did not send one
+    #[must_use]
+    pub fn counter_bank_name(&self) -> Option<&str> {
+        self.bank_name.as_deref().or_else(|| {
+            self.bank_code
+                .as_deref()
+                .and_then(bank_code::lookup)
+                .map(|bank| bank.name)
+        })
+    }
+}
+
+fn value<'a>(raw: &'a RawMovement, column: &str) -> Option<&'a TransactionDataEnum> {
+    raw.get(column)
+        .and_then(Option::as_ref)
+        .map(|data| &data.value)
+}
+
+fn string(raw: &RawMovement, column: &str) -> Option<String> {
+    match value(raw, column)? {
+        TransactionDataEnum::String(v) => Some(v.clone()),
+        TransactionDataEnum::Integer(v) => Some(v.to_string()),
+        TransactionDataEnum::Decimal(v) => Some(v.to_string()),
+    }
+}
+
+/// Symbol column; values that are not valid symbols are dropped with a warning
+fn symbol<T: FromStr>(raw: &RawMovement, column: &str) -> Option<T> {
+    let value = string(raw, column)?;
+    let parsed = value.parse().ok();
+    if parsed.is_none() {
+        warn!("Ignoring invalid symbol {value:?} in {column}");
+    }
+    parsed
+}
+
+/// Column name of the payment reason
+const PAYMENT_REASON_COLUMN: &str = "Platební titul";
+
+/// Payment reason column; invalid codes are dropped with a warning
+fn payment_reason(raw: &RawMovement) -> Option<PaymentReason> {
+    let data = raw
+        .values()
+        .flatten()
+        .find(|data| data.name == PAYMENT_REASON_COLUMN)?;
+    match PaymentReason::try_from(&data.value) {
+        Ok(reason) => Some(reason),
+        Err(e) => {
+            warn!("Ignoring payment reason in column{}: {e}", data.id);
+            None
+        }
+    }
+}
+
+fn integer(raw: &RawMovement, column: &str) -> Option<i64> {
+    match value(raw, column)? {
+        TransactionDataEnum::Integer(v) => Some(*v),
+        TransactionDataEnum::String(v) => v.trim().parse().ok(),
+        TransactionDataEnum::Decimal(_) => None,
+    }
+}
+
+fn decimal(raw: &RawMovement, column: &str) -> Option<Decimal> {
+    match value(raw, column)? {
+        TransactionDataEnum::Decimal(v) => Some(*v),
+        TransactionDataEnum::Integer(v) => Some(Decimal::from(*v)),
+        TransactionDataEnum::String(v) => v.trim().parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(id: i64, name: &str, value: TransactionDataEnum) -> Option<TransactionData> {
+        Some(TransactionData {
+            value,
+            name: name.to_string(),
+            id,
+        })
+    }
+
+    fn sample() -> RawMovement {
+        let mut raw = RawMovement::new();
+        raw.insert(
+            "column22".to_string(),
+            column(22, "ID pohybu", TransactionDataEnum::Integer(1_147_608_196)),
+        );
+        raw.insert(
+            "column0".to_string(),
+            column(
+                0,
+                "Datum",
+                TransactionDataEnum::String("2024-01-15+0100".to_string()),
+            ),
+        );
+        raw.insert(
+            "column1".to_string(),
+            column(
+                1,
+                "Objem",
+                TransactionDataEnum::Decimal(Decimal::new(-15050, 2)),
+            ),
+        );
+        raw.insert(
+            "column14".to_string(),
+            column(14, "Měna", TransactionDataEnum::String("CZK".to_string())),
+        );
+        raw.insert(
+            "column5".to_string(),
+            column(5, "VS", TransactionDataEnum::String("1234".to_string())),
+        );
+        raw.insert("column4".to_string(), None);
+        raw
+    }
+
+    #[test]
+    fn from_raw_extracts_columns() {
+        let movement = Movement::from_raw(&sample()).unwrap();
+        assert_eq!(movement.id, 1_147_608_196);
+        assert_eq!(movement.amount, Decimal::new(-15050, 2));
+        assert_eq!(movement.currency, Currency::Czk);
+        assert_eq!(movement.vs, Some("1234".parse().unwrap()));
+        assert_eq!(movement.ks, None);
+        assert_eq!(movement.payment_reason, None);
+        assert_eq!(movement.day(), "2024-01-15");
+    }
+
+    #[test]
+    fn from_raw_payment_reason() {
+        let mut raw = sample();
+        raw.insert(
+            "column30".to_string(),
+            column(30, "Platební titul", TransactionDataEnum::Integer(110)),
+        );
+        let movement = Movement::from_raw(&raw).unwrap();
+        assert_eq!(
+            movement.payment_reason,
+            Some(PaymentReason::ExportImportGoods)
+        );
+        raw.insert(
+            "column30".to_string(),
+            column(
+                30,
+                "Platební titul",
+                TransactionDataEnum::String("x".to_string()),
+            ),
+        );
+        assert_eq!(Movement::from_raw(&raw).unwrap().payment_reason, None);
+    }
+
+    #[test]
+    fn from_raw_integer_amount() {
+        let mut raw = sample();
+        raw.insert(
+            "column1".to_string(),
+            column(1, "Objem", TransactionDataEnum::Integer(500)),
+        );
+        let movement = Movement::from_raw(&raw).unwrap();
+        assert_eq!(movement.amount, Decimal::new(500, 0));
+    }
+
+    #[test]
+    fn from_raw_missing_id() {
+        let mut raw = sample();
+        raw.remove("column22");
+        assert!(matches!(
+            Movement::from_raw(&raw),
+            Err(Error::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn counter_bank_name_falls_back_to_registry() {
+        let mut movement = Movement::from_raw(&sample()).unwrap();
+        assert_eq!(movement.counter_bank_name(), None);
+        movement.bank_code = Some("0300".to_string());
+        assert_eq!(
+            movement.counter_bank_name(),
+            Some("Československá obchodní banka, a. s.")
+        );
+        movement.bank_name = Some("ČSOB".to_string());
+        assert_eq!(movement.counter_bank_name(), Some("ČSOB"));
+    }
+}
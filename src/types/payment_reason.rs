@@ -0,0 +1,253 @@
+//! ČNB statistical payment reason codes
+//!
+//! Foreign and euro payments carry a three digit payment reason ("platební
+//! titul") from the ČNB balance of payments code list, which Fio requires for
+//! foreign payments.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::types::account_statement::TransactionDataEnum;
+
+macro_rules! payment_reasons {
+    ($($variant:ident => $code:literal, $description:literal;)*) => {
+        /// Payment reason code
+        ///
+        /// Codes missing from this catalogue can still be used through
+        /// [`PaymentReason::from_code`], which returns [`PaymentReason::Other`].
+        ///
+        /// ```
+        /// use fiocz_rs::types::payment_reason::PaymentReason;
+        ///
+        /// let reason: PaymentReason = "110".parse().unwrap();
+        /// assert_eq!(reason, PaymentReason::ExportImportGoods);
+        /// assert_eq!(reason.description(), Some("Export and import of goods"));
+        /// assert_eq!("999".parse::<PaymentReason>().unwrap(), PaymentReason::from_code(999));
+        /// assert_eq!(PaymentReason::from_code(110), PaymentReason::ExportImportGoods);
+        /// ```
+        #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub enum PaymentReason {
+            $(
+                #[doc = $description]
+                $variant,
+            )*
+            /// Code not in the catalogue, see [`PaymentReason::from_code`]
+            Other(OtherReason),
+        }
+
+        const KNOWN: &[PaymentReason] = &[$(PaymentReason::$variant),*];
+
+        impl PaymentReason {
+            /// Numeric code
+            #[must_use]
+            pub const fn code(self) -> u16 {
+                match self {
+                    $(Self::$variant => $code,)*
+                    Self::Other(code) => code.0,
+                }
+            }
+
+            /// Description of a catalogued code
+            #[must_use]
+            pub const fn description(self) -> Option<&'static str> {
+                match self {
+                    $(Self::$variant => Some($description),)*
+                    Self::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+/// Payment reason code missing from the catalogue
+///
+/// Only created through [`PaymentReason::from_code`] or parsing, so a
+/// catalogued code is never represented as [`PaymentReason::Other`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct OtherReason(u16);
+
+impl OtherReason {
+    /// Numeric code
+    #[must_use]
+    pub const fn code(self) -> u16 {
+        self.0
+    }
+}
+
+payment_reasons! {
+    ExportImportGoods => 110, "Export and import of goods";
+    GoodsForProcessing => 112, "Goods sent for or returned from processing";
+    Merchanting => 115, "Merchanting";
+    RepairsOfGoods => 120, "Repairs of goods";
+    NonMonetaryGold => 131, "Non-monetary gold";
+    OtherGoods => 150, "Other goods transactions";
+    FreightTransport => 210, "Freight transport";
+    PassengerTransport => 220, "Passenger transport";
+    OtherTransport => 230, "Other transport services";
+    BusinessTravel => 240, "Business travel";
+    PrivateTravel => 250, "Private travel";
+    PostalServices => 260, "Postal and courier services";
+    Telecommunication => 270, "Telecommunication services";
+    Construction => 280, "Construction services";
+    Insurance => 290, "Insurance services";
+    FinancialServices => 310, "Financial services";
+    ComputerServices => 320, "Computer and information services";
+    Royalties => 330, "Royalties and licence fees";
+    ResearchAndDevelopment => 340, "Research and development";
+    ProfessionalServices => 350, "Legal, accounting and consulting services";
+    Advertising => 360, "Advertising and market research";
+    TechnicalServices => 370, "Architectural and engineering services";
+    OtherBusinessServices => 380, "Other business services";
+    PersonalServices => 390, "Personal, cultural and recreational services";
+    GovernmentServices => 395, "Government services";
+    CompensationOfEmployees => 410, "Compensation of employees";
+    Dividends => 420, "Dividends";
+    Interest => 430, "Interest";
+    OtherInvestmentIncome => 440, "Other investment income";
+    Pensions => 510, "Pensions and social benefits";
+    Gifts => 520, "Gifts and donations";
+    TaxesAndFees => 530, "Taxes and fees";
+    OtherCurrentTransfers => 540, "Other current transfers";
+    CapitalTransfers => 550, "Capital transfers";
+    DirectInvestment => 610, "Direct investment";
+    PortfolioEquity => 620, "Portfolio investment in equity";
+    PortfolioDebt => 630, "Portfolio investment in debt securities";
+    Derivatives => 640, "Financial derivatives";
+    Loans => 710, "Loans and credits";
+    Deposits => 720, "Deposits";
+    TradeCredits => 730, "Trade credits and advances";
+    OtherFinancial => 740, "Other financial transactions";
+    OwnAccountTransfer => 801, "Transfer between own accounts";
+    Refund => 802, "Refund of a previous payment";
+    OtherPayments => 803, "Other payments";
+}
+
+impl PaymentReason {
+    /// All catalogued payment reasons, sorted by code
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        KNOWN
+    }
+
+    /// Payment reason for a numeric code, [`PaymentReason::Other`] if not catalogued
+    #[must_use]
+    pub fn from_code(code: u16) -> Self {
+        KNOWN
+            .iter()
+            .copied()
+            .find(|reason| reason.code() == code)
+            .unwrap_or(Self::Other(OtherReason(code)))
+    }
+
+    /// Whether the code has the required three digits
+    #[must_use]
+    pub const fn is_valid(self) -> bool {
+        matches!(self.code(), 100..=999)
+    }
+}
+
+impl fmt::Display for PaymentReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for PaymentReason {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim();
+        if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidPaymentReason(s.to_string()));
+        }
+        code.parse()
+            .map(Self::from_code)
+            .map_err(|_| Error::InvalidPaymentReason(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for PaymentReason {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PaymentReason> for String {
+    fn from(value: PaymentReason) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<&TransactionDataEnum> for PaymentReason {
+    type Error = Error;
+
+    /// Parse a payment reason from a statement column, sent either as text or number
+    fn try_from(value: &TransactionDataEnum) -> Result<Self, Self::Error> {
+        match value {
+            TransactionDataEnum::String(v) => v.parse(),
+            TransactionDataEnum::Integer(v) => v.to_string().parse(),
+            TransactionDataEnum::Decimal(v) => Err(Error::InvalidPaymentReason(v.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_sorted_and_valid() {
+        assert!(KNOWN.windows(2).all(|w| w[0].code() < w[1].code()));
+        assert!(KNOWN
+            .iter()
+            .all(|r| r.is_valid() && r.description().is_some()));
+        for reason in KNOWN {
+            assert_eq!(PaymentReason::from_code(reason.code()), *reason);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "430".parse::<PaymentReason>().unwrap(),
+            PaymentReason::Interest
+        );
+        assert_eq!(
+            "123".parse::<PaymentReason>().unwrap(),
+            PaymentReason::Other(OtherReason(123))
+        );
+        for input in ["11", "1100", "abc", ""] {
+            assert!(matches!(
+                input.parse::<PaymentReason>(),
+                Err(Error::InvalidPaymentReason(_))
+            ));
+        }
+        assert!(!PaymentReason::from_code(42).is_valid());
+    }
+
+    #[test]
+    fn from_statement_column() {
+        assert_eq!(
+            PaymentReason::try_from(&TransactionDataEnum::Integer(110)).unwrap(),
+            PaymentReason::ExportImportGoods
+        );
+        assert_eq!(
+            PaymentReason::try_from(&TransactionDataEnum::String("520".into())).unwrap(),
+            PaymentReason::Gifts
+        );
+    }
+
+    #[test]
+    fn serde_as_string() {
+        let json = serde_json::to_string(&PaymentReason::Dividends).unwrap();
+        assert_eq!(json, "\"420\"");
+        assert_eq!(
+            serde_json::from_str::<PaymentReason>(&json).unwrap(),
+            PaymentReason::Dividends
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Czech payment symbols (variable, constant and specific symbol)
+//!
+//! Symbols are numeric identifiers of up to 10 (VS, SS) or 4 (KS) digits.
+//! Leading zeros carry no meaning, so `0001234` and `1234` are the same
+//! symbol; values are stored without them so that comparing symbols from
+//! imports, statements and user input works reliably. The import XML writes
+//! the constant symbol padded to four digits, as the bank expects.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+macro_rules! symbol {
+    ($(#[$meta:meta])* $name:ident, $label:literal, $max_len:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+        #[serde(try_from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            /// Maximum number of digits
+            pub const MAX_LEN: usize = $max_len;
+
+            /// Symbol without leading zeros
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Symbol padded with leading zeros to the maximum length
+            #[must_use]
+            pub fn padded(&self) -> String {
+                format!("{:0>width$}", self.0, width = Self::MAX_LEN)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                normalise(s, Self::MAX_LEN)
+                    .map(Self)
+                    .ok_or_else(|| {
+                        Error::InvalidSymbol(format!(
+                            "{} {s:?} is not a number of up to {} digits",
+                            $label,
+                            Self::MAX_LEN
+                        ))
+                    })
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = Error;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                value.parse()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+symbol!(
+    /// Variable symbol (VS), up to 10 digits, usually identifying an invoice
+    ///
+    /// ```
+    /// use fiocz_rs::types::symbol::VariableSymbol;
+    ///
+    /// let vs: VariableSymbol = "0001234".parse().unwrap();
+    /// assert_eq!(vs, "1234".parse().unwrap());
+    /// assert_eq!(vs.to_string(), "1234");
+    /// ```
+    VariableSymbol,
+    "Variable symbol",
+    10
+);
+
+symbol!(
+    /// Constant symbol (KS), up to 4 digits, describing the kind of payment
+    ConstantSymbol,
+    "Constant symbol",
+    4
+);
+
+symbol!(
+    /// Specific symbol (SS), up to 10 digits
+    SpecificSymbol,
+    "Specific symbol",
+    10
+);
+
+/// Strip surrounding whitespace and leading zeros, `None` if not a number of
+/// up to `max_len` significant digits
+fn normalise(value: &str, max_len: usize) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let trimmed = value.trim_start_matches('0');
+    let trimmed = if trimmed.is_empty() { "0" } else { trimmed };
+    (trimmed.len() <= max_len).then(|| trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leading_zeros_normalised() {
+        let vs: VariableSymbol = " 0000012345 ".parse().unwrap();
+        assert_eq!(vs.as_str(), "12345");
+        assert_eq!(vs.padded(), "0000012345");
+        let ks: ConstantSymbol = "0308".parse().unwrap();
+        assert_eq!(ks.to_string(), "308");
+        assert_eq!(ks.padded(), "0308");
+        assert_eq!("000".parse::<SpecificSymbol>().unwrap().as_str(), "0");
+    }
+
+    #[test]
+    fn limits() {
+        assert!("1234567890".parse::<VariableSymbol>().is_ok());
+        assert!("12345678901".parse::<VariableSymbol>().is_err());
+        assert!("12345".parse::<ConstantSymbol>().is_err());
+        assert_eq!(
+            "000001234".parse::<ConstantSymbol>().unwrap().as_str(),
+            "1234"
+        );
+        assert!("0012345678901".parse::<VariableSymbol>().is_err());
+        assert!(matches!(
+            "12a".parse::<SpecificSymbol>(),
+            Err(Error::InvalidSymbol(_))
+        ));
+        assert!("".parse::<VariableSymbol>().is_err());
+    }
+
+    #[test]
+    fn serde_as_string() {
+        let vs: VariableSymbol = serde_json::from_str("\"00042\"").unwrap();
+        assert_eq!(serde_json::to_string(&vs).unwrap(), "\"42\"");
+        assert!(serde_json::from_str::<VariableSymbol>("\"x\"").is_err());
+    }
+}
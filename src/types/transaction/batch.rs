@@ -0,0 +1,287 @@
+//! Splitting imports into batches the bank accepts
+use super::{Import, Type};
+use crate::error::Error;
+use crate::types::import_response::ImportResponse;
+
+/// Size limits of one import batch
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BatchLimits {
+    /// Maximum number of orders
+    pub max_orders: usize,
+    /// Maximum size of the XML file in bytes
+    pub max_bytes: usize,
+}
+
+impl Default for BatchLimits {
+    /// Conservative limits of 500 orders and 1 MiB per file
+    fn default() -> Self {
+        Self {
+            max_orders: 500,
+            max_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// Part of an import submitted in one request
+#[derive(Debug, Eq, PartialEq)]
+pub struct Batch {
+    /// Orders of the batch
+    pub import: Import,
+    /// Index in the original [`Import::orders`] of each order in the XML
+    /// file, whose position the bank reports in [`ImportResponse::details`]
+    pub indices: Vec<usize>,
+}
+
+impl Import {
+    /// Split into batches within the limits
+    ///
+    /// The XML file lists domestic, then euro, then foreign orders; batches
+    /// follow that order so each batch file keeps the original positions
+    /// within its type. An order larger than `max_bytes` on its own forms a
+    /// batch by itself and is left for the bank to reject.
+    #[must_use]
+    pub fn into_batches(self, limits: &BatchLimits) -> Vec<Batch> {
+        let envelope = Self::new().to_xml().len();
+        let mut orders: Vec<(usize, Type)> = self.orders.into_iter().enumerate().collect();
+        orders.sort_by_key(|(_, order)| match order {
+            Type::Domestic(_) => 0,
+            Type::Euro(_) => 1,
+            Type::Foreign(_) => 2,
+        });
+
+        let mut batches = vec![];
+        let mut current = Batch {
+            import: Self::new(),
+            indices: vec![],
+        };
+        let mut size = envelope;
+        for (index, order) in orders {
+            let order_size = Self {
+                orders: vec![order.clone()],
+            }
+            .to_xml()
+            .len()
+                - envelope;
+            let full = current.indices.len() >= limits.max_orders.max(1)
+                || size + order_size > limits.max_bytes;
+            if full && !current.indices.is_empty() {
+                batches.push(std::mem::replace(
+                    &mut current,
+                    Batch {
+                        import: Self::new(),
+                        indices: vec![],
+                    },
+                ));
+                size = envelope;
+            }
+            current.import.orders.push(order);
+            current.indices.push(index);
+            size += order_size;
+        }
+        if !current.indices.is_empty() {
+            batches.push(current);
+        }
+        batches
+    }
+}
+
+/// Outcome of one order of [`crate::Fio::import_in_batches`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OrderStatus {
+    /// Accepted by the bank
+    Accepted,
+    /// Accepted with warnings
+    Warning(String),
+    /// Rejected, with the bank's messages or the batch status
+    Rejected(String),
+    /// Not accepted because the request of its batch or an earlier one failed
+    NotSubmitted,
+    /// Not sent again because the import ledger records its batch as
+    /// submitted, see [`crate::ledger::LedgerImporter::import_in_batches`]
+    AlreadySubmitted,
+}
+
+/// Result of one order of [`crate::Fio::import_in_batches`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OrderResult {
+    /// Index of the batch the order was sent in
+    pub batch: usize,
+    /// Instruction ID the bank assigned to the batch
+    pub id_instruction: Option<String>,
+    /// Outcome of the order
+    pub status: OrderStatus,
+}
+
+/// Result of [`crate::Fio::import_in_batches`]
+#[derive(Debug)]
+pub struct BatchImportReport {
+    /// Responses of the submitted batches, in order
+    pub responses: Vec<ImportResponse>,
+    /// Result of each order, at the index of the original [`Import::orders`]
+    pub orders: Vec<OrderResult>,
+    /// Error that stopped the submission of further batches
+    pub error: Option<Error>,
+}
+
+impl BatchImportReport {
+    /// Prepare a report with all orders not submitted yet
+    pub(crate) fn new(batches: &[Batch]) -> Self {
+        let count = batches.iter().map(|b| b.indices.len()).sum();
+        let mut orders = vec![
+            OrderResult {
+                batch: 0,
+                id_instruction: None,
+                status: OrderStatus::NotSubmitted,
+            };
+            count
+        ];
+        for (number, batch) in batches.iter().enumerate() {
+            for index in &batch.indices {
+                orders[*index].batch = number;
+            }
+        }
+        Self {
+            responses: vec![],
+            orders,
+            error: None,
+        }
+    }
+
+    /// Record the bank's response to a batch
+    pub(crate) fn record(&mut self, batch: &Batch, response: ImportResponse) {
+        for (position, index) in batch.indices.iter().enumerate() {
+            let messages = response.messages(position + 1);
+            let text = |status: &str| {
+                messages
+                    .iter()
+                    .filter(|m| m.status == status)
+                    .map(|m| m.text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            };
+            let status = if !response.is_ok() {
+                let errors = text("error");
+                OrderStatus::Rejected(if errors.is_empty() {
+                    format!("batch rejected with error code {}", response.error_code)
+                } else {
+                    errors
+                })
+            } else if messages.iter().any(|m| m.status == "warning") {
+                OrderStatus::Warning(text("warning"))
+            } else {
+                OrderStatus::Accepted
+            };
+            let result = &mut self.orders[*index];
+            result.id_instruction.clone_from(&response.id_instruction);
+            result.status = status;
+        }
+        self.responses.push(response);
+    }
+
+    /// Record a batch skipped because it was submitted before
+    pub(crate) fn skip(&mut self, batch: &Batch, id_instruction: Option<String>) {
+        for index in &batch.indices {
+            let result = &mut self.orders[*index];
+            result.id_instruction.clone_from(&id_instruction);
+            result.status = OrderStatus::AlreadySubmitted;
+        }
+    }
+
+    /// Whether all batches were submitted and every order accepted
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none()
+            && self.orders.iter().all(|o| {
+                matches!(
+                    o.status,
+                    OrderStatus::Accepted | OrderStatus::Warning(_) | OrderStatus::AlreadySubmitted
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{domestic, euro};
+
+    fn import() -> Import {
+        Import {
+            orders: vec![
+                Type::Euro(euro(10)),
+                Type::Domestic(domestic(1)),
+                Type::Domestic(domestic(2)),
+                Type::Euro(euro(10)),
+                Type::Domestic(domestic(3)),
+            ],
+        }
+    }
+
+    #[test]
+    fn split_by_count_in_xml_order() {
+        let limits = BatchLimits {
+            max_orders: 2,
+            ..BatchLimits::default()
+        };
+        let batches = import().into_batches(&limits);
+        let indices: Vec<_> = batches.iter().map(|b| b.indices.clone()).collect();
+        assert_eq!(indices, [vec![1, 2], vec![4, 0], vec![3]]);
+        assert_eq!(batches[1].import.orders[0], Type::Domestic(domestic(3)));
+    }
+
+    #[test]
+    fn split_by_size() {
+        let order_size = Import {
+            orders: vec![Type::Domestic(domestic(1))],
+        }
+        .to_xml()
+        .len();
+        let limits = BatchLimits {
+            max_orders: 100,
+            max_bytes: order_size + 10,
+        };
+        let batches = import().into_batches(&limits);
+        assert_eq!(batches.len(), 5);
+        assert!(batches
+            .iter()
+            .all(|b| b.import.to_xml().len() <= limits.max_bytes || b.indices.len() == 1));
+        assert!(Import::new().into_batches(&limits).is_empty());
+    }
+
+    #[test]
+    fn report_maps_orders() {
+        let limits = BatchLimits {
+            max_orders: 2,
+            ..BatchLimits::default()
+        };
+        let batches = import().into_batches(&limits);
+        let mut report = BatchImportReport::new(&batches);
+        report.record(
+            &batches[0],
+            ImportResponse::from_xml(
+                "<result><errorCode>0</errorCode><idInstruction>7</idInstruction></result>",
+            )
+            .unwrap(),
+        );
+        report.record(
+            &batches[1],
+            ImportResponse::from_xml(
+                "<result><errorCode>1</errorCode></result><ordersDetails><detail id=\"2\">\
+                 <messages><message status=\"error\" errorCode=\"1016\">Bad IBAN</message>\
+                 </messages></detail></ordersDetails>",
+            )
+            .unwrap(),
+        );
+        assert_eq!(report.orders[1].status, OrderStatus::Accepted);
+        assert_eq!(report.orders[2].id_instruction.as_deref(), Some("7"));
+        assert_eq!(
+            report.orders[0].status,
+            OrderStatus::Rejected("Bad IBAN".to_string())
+        );
+        assert_eq!(report.orders[4].batch, 1);
+        assert!(matches!(report.orders[4].status, OrderStatus::Rejected(_)));
+        assert_eq!(report.orders[3].batch, 2);
+        assert_eq!(report.orders[3].status, OrderStatus::NotSubmitted);
+        assert!(!report.is_complete());
+    }
+}
@@ -0,0 +1,865 @@
+//! Builders for single import orders
+//!
+//! Each builder checks values as they are set and collects the problems;
+//! `build()` adds missing mandatory fields and returns all of them at once as
+//! [`Error::InvalidFields`]. A built order passes [`super::Import::validate`].
+use chrono::{Local, NaiveDate};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+use super::validate::text_problem;
+use super::{
+    DetailsOfCharges, DomesticPaymentType, DomesticTransaction, EuroPaymentType,
+    ForeignTransaction, T2Transaction, FIO_BANK_CODE,
+};
+use crate::error::{Error, FieldError};
+use crate::types::account_number::CzAccountNumber;
+use crate::types::bic::Bic;
+use crate::types::currency::{Currency, Money};
+use crate::types::iban::Iban;
+use crate::types::payment_reason::PaymentReason;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+
+/// Field errors collected by a builder
+#[derive(Debug, Default)]
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+    fn push(&mut self, field: &'static str, message: impl Into<String>) {
+        self.0.retain(|e| e.field != field);
+        self.0.push(FieldError {
+            field,
+            message: message.into(),
+        });
+    }
+
+    fn clear(&mut self, field: &'static str) {
+        self.0.retain(|e| e.field != field);
+    }
+
+    fn check<T>(&mut self, field: &'static str, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.clear(field);
+                Some(value)
+            }
+            Err(e) => {
+                self.push(field, e.to_string());
+                None
+            }
+        }
+    }
+
+    fn parse<T: FromStr<Err = Error>>(&mut self, field: &'static str, value: &str) -> Option<T> {
+        self.check(field, value.parse())
+    }
+
+    fn text(
+        &mut self,
+        field: &'static str,
+        value: &str,
+        max_len: usize,
+        sepa: bool,
+    ) -> Option<String> {
+        match text_problem(value, max_len, sepa) {
+            Some(problem) => {
+                self.push(field, problem);
+                None
+            }
+            None => {
+                self.clear(field);
+                Some(value.to_string())
+            }
+        }
+    }
+
+    fn account_from(&mut self, value: &str) -> Option<String> {
+        self.check(
+            "accountFrom",
+            CzAccountNumber::parse_with_bank(value, FIO_BANK_CODE),
+        )
+        .map(|account| account.account())
+    }
+
+    fn date(&mut self, value: &str) -> Option<NaiveDate> {
+        match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) if date < today() => {
+                self.push("date", "must not be in the past");
+                None
+            }
+            result => self.check("date", result.map_err(|_| Error::InvalidDateFormat)),
+        }
+    }
+
+    fn currency(&mut self, currency: Currency, only: Option<Currency>) -> Option<Currency> {
+        match only {
+            Some(only) if currency != only => {
+                self.push("currency", format!("must be {only}"));
+                None
+            }
+            _ if !currency.is_known() => {
+                self.push("currency", format!("unknown currency {currency}"));
+                None
+            }
+            _ => {
+                self.clear("currency");
+                Some(currency)
+            }
+        }
+    }
+
+    fn payment_reason(&mut self, reason: PaymentReason) -> Option<PaymentReason> {
+        if reason.is_valid() {
+            self.clear("paymentReason");
+            Some(reason)
+        } else {
+            self.push("paymentReason", "must be a three digit code");
+            None
+        }
+    }
+
+    fn country(&mut self, field: &'static str, value: &str) -> Option<String> {
+        if value.len() == 2 && value.bytes().all(|b| b.is_ascii_uppercase()) {
+            self.clear(field);
+            Some(value.to_string())
+        } else {
+            self.push(field, "must be a two letter ISO 3166 code");
+            None
+        }
+    }
+
+    fn amount(&mut self, amount: Decimal, currency: Currency) {
+        if amount <= Decimal::ZERO {
+            self.push("amount", "must be greater than zero");
+        } else if let Err(e) = Money::exact(amount, currency) {
+            self.push("amount", e.to_string());
+        }
+    }
+
+    /// Report a mandatory field unless it was set, or set to an invalid value
+    fn required<T>(&mut self, field: &'static str, value: &Option<T>) {
+        if value.is_none() && !self.0.iter().any(|e| e.field == field) {
+            self.push(field, "is required");
+        }
+    }
+
+    fn into_result<T>(self, value: Option<T>) -> Result<T, Error> {
+        match value {
+            Some(value) if self.0.is_empty() => Ok(value),
+            _ => Err(Error::InvalidFields(self.0)),
+        }
+    }
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+macro_rules! text_setter {
+    ($(#[$meta:meta])* $name:ident, $field:literal, $max_len:literal, $sepa:literal) => {
+        $(#[$meta])*
+        #[must_use]
+        pub fn $name(mut self, value: &str) -> Self {
+            self.$name = self.errors.text($field, value, $max_len, $sepa);
+            self
+        }
+    };
+}
+
+macro_rules! parse_setter {
+    ($(#[$meta:meta])* $name:ident, $field:literal) => {
+        $(#[$meta])*
+        #[must_use]
+        pub fn $name(mut self, value: &str) -> Self {
+            self.$name = self.errors.parse($field, value);
+            self
+        }
+    };
+}
+
+macro_rules! common_setters {
+    () => {
+        /// Sender's Fio account (`prefix-number`)
+        #[must_use]
+        pub fn account_from(mut self, account: &str) -> Self {
+            self.account_from = self.errors.account_from(account);
+            self
+        }
+
+        /// Amount, checked against the order currency on [`Self::build`]
+        #[must_use]
+        pub const fn amount(mut self, amount: Decimal) -> Self {
+            self.amount = Some(amount);
+            self
+        }
+
+        /// Due date in format YYYY-MM-DD, defaults to today
+        #[must_use]
+        pub fn date(mut self, date: &str) -> Self {
+            self.date = self.errors.date(date);
+            self
+        }
+
+        text_setter!(
+            /// Comment for the sender, up to 255 characters
+            comment,
+            "comment",
+            255,
+            false
+        );
+    };
+}
+
+macro_rules! symbol_setters {
+    () => {
+        parse_setter!(
+            /// Constant symbol
+            ks,
+            "ks"
+        );
+        parse_setter!(
+            /// Variable symbol
+            vs,
+            "vs"
+        );
+        parse_setter!(
+            /// Specific symbol
+            ss,
+            "ss"
+        );
+
+        /// Payment reason
+        #[must_use]
+        pub fn payment_reason(mut self, reason: PaymentReason) -> Self {
+            self.payment_reason = self.errors.payment_reason(reason);
+            self
+        }
+    };
+}
+
+/// Builder of [`DomesticTransaction`]
+///
+/// Mandatory: [`account_from`](Self::account_from), [`amount`](Self::amount)
+/// and [`account_to`](Self::account_to). The currency defaults to CZK, the
+/// date to today and the payment type to [`DomesticPaymentType::Standard`].
+///
+/// ```
+/// use fiocz_rs::types::transaction::DomesticTransaction;
+/// use rust_decimal::Decimal;
+///
+/// let order = DomesticTransaction::builder()
+///     .account_from("2101179627")
+///     .amount(Decimal::new(10050, 2))
+///     .account_to("19-2000145399/0800")
+///     .vs("1234567890")
+///     .build()
+///     .unwrap();
+/// assert_eq!(order.bank_code, "0800");
+/// ```
+#[derive(Debug, Default)]
+pub struct DomesticTransactionBuilder {
+    errors: Errors,
+    account_from: Option<String>,
+    currency: Option<Currency>,
+    amount: Option<Decimal>,
+    account_to: Option<CzAccountNumber>,
+    ks: Option<ConstantSymbol>,
+    vs: Option<VariableSymbol>,
+    ss: Option<SpecificSymbol>,
+    date: Option<NaiveDate>,
+    message_for_recipient: Option<String>,
+    comment: Option<String>,
+    payment_reason: Option<PaymentReason>,
+    payment_type: Option<DomesticPaymentType>,
+}
+
+impl DomesticTransaction {
+    /// Create a builder checking every field
+    #[must_use]
+    pub fn builder() -> DomesticTransactionBuilder {
+        DomesticTransactionBuilder::default()
+    }
+}
+
+impl DomesticTransactionBuilder {
+    common_setters!();
+    symbol_setters!();
+
+    /// Currency, defaults to CZK; domestic payments accept CZK only
+    #[must_use]
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = self.errors.currency(currency, Some(Currency::Czk));
+        self
+    }
+
+    /// Recipient account including bank code (`prefix-number/bank`)
+    #[must_use]
+    pub fn account_to(mut self, account: &str) -> Self {
+        self.account_to = self.errors.check(
+            "accountTo",
+            account.parse::<CzAccountNumber>().and_then(|account| {
+                if account.bank().is_some() {
+                    Ok(account)
+                } else {
+                    Err(Error::UnknownBankCode(account.bank_code().to_string()))
+                }
+            }),
+        );
+        self
+    }
+
+    text_setter!(
+        /// Message for the recipient, up to 140 characters
+        message_for_recipient,
+        "messageForRecipient",
+        140,
+        false
+    );
+
+    /// Payment type, defaults to [`DomesticPaymentType::Standard`]
+    #[must_use]
+    pub const fn payment_type(mut self, payment_type: DomesticPaymentType) -> Self {
+        self.payment_type = Some(payment_type);
+        self
+    }
+
+    /// Build the order
+    /// # Errors
+    /// * `Error::InvalidFields` - Invalid values or missing mandatory fields
+    pub fn build(mut self) -> Result<DomesticTransaction, Error> {
+        let currency = self.currency.unwrap_or(Currency::Czk);
+        self.errors.required("accountFrom", &self.account_from);
+        self.errors.required("amount", &self.amount);
+        self.errors.required("accountTo", &self.account_to);
+        if let Some(amount) = self.amount {
+            self.errors.amount(amount, currency);
+        }
+        let order = match (self.account_from, self.amount, self.account_to) {
+            (Some(account_from), Some(amount), Some(account_to)) => Some(DomesticTransaction {
+                account_from,
+                currency,
+                amount,
+                account_to: account_to.account(),
+                bank_code: account_to.bank_code().to_string(),
+                ks: self.ks,
+                vs: self.vs,
+                ss: self.ss,
+                date: self.date.unwrap_or_else(today).to_string(),
+                message_for_recipient: self.message_for_recipient,
+                comment: self.comment,
+                payment_reason: self.payment_reason,
+                payment_type: Some(self.payment_type.unwrap_or(DomesticPaymentType::Standard)),
+            }),
+            _ => None,
+        };
+        self.errors.into_result(order)
+    }
+}
+
+/// Builder of [`T2Transaction`]
+///
+/// Mandatory: [`account_from`](Self::account_from), [`amount`](Self::amount),
+/// [`account_to`](Self::account_to) and [`benef_name`](Self::benef_name). The
+/// currency is always EUR, the date defaults to today and the payment type to
+/// [`EuroPaymentType::Standard`].
+#[derive(Debug, Default)]
+pub struct T2TransactionBuilder {
+    errors: Errors,
+    account_from: Option<String>,
+    amount: Option<Decimal>,
+    account_to: Option<Iban>,
+    bic: Option<Bic>,
+    ks: Option<ConstantSymbol>,
+    vs: Option<VariableSymbol>,
+    ss: Option<SpecificSymbol>,
+    date: Option<NaiveDate>,
+    benef_name: Option<String>,
+    benef_street: Option<String>,
+    benef_city: Option<String>,
+    benef_country: Option<String>,
+    remittance_info1: Option<String>,
+    remittance_info2: Option<String>,
+    remittance_info3: Option<String>,
+    comment: Option<String>,
+    payment_reason: Option<PaymentReason>,
+    payment_type: Option<EuroPaymentType>,
+}
+
+impl T2Transaction {
+    /// Create a builder checking every field
+    #[must_use]
+    pub fn builder() -> T2TransactionBuilder {
+        T2TransactionBuilder::default()
+    }
+}
+
+impl T2TransactionBuilder {
+    common_setters!();
+    symbol_setters!();
+
+    parse_setter!(
+        /// Recipient IBAN
+        account_to,
+        "accountTo"
+    );
+    parse_setter!(
+        /// Recipient bank BIC
+        bic,
+        "bic"
+    );
+    text_setter!(
+        /// Beneficiary name, up to 35 characters
+        benef_name,
+        "benefName",
+        35,
+        true
+    );
+    text_setter!(
+        /// Beneficiary street, up to 35 characters
+        benef_street,
+        "benefStreet",
+        35,
+        true
+    );
+    text_setter!(
+        /// Beneficiary city, up to 35 characters
+        benef_city,
+        "benefCity",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 1, up to 35 characters
+        remittance_info1,
+        "remittanceInfo1",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 2, up to 35 characters
+        remittance_info2,
+        "remittanceInfo2",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 3, up to 35 characters
+        remittance_info3,
+        "remittanceInfo3",
+        35,
+        true
+    );
+
+    /// Beneficiary country as two letter ISO 3166 code
+    #[must_use]
+    pub fn benef_country(mut self, country: &str) -> Self {
+        self.benef_country = self.errors.country("benefCountry", country);
+        self
+    }
+
+    /// Payment type, defaults to [`EuroPaymentType::Standard`]
+    #[must_use]
+    pub const fn payment_type(mut self, payment_type: EuroPaymentType) -> Self {
+        self.payment_type = Some(payment_type);
+        self
+    }
+
+    /// Build the order
+    /// # Errors
+    /// * `Error::InvalidFields` - Invalid values or missing mandatory fields
+    pub fn build(mut self) -> Result<T2Transaction, Error> {
+        self.errors.required("accountFrom", &self.account_from);
+        self.errors.required("amount", &self.amount);
+        self.errors.required("accountTo", &self.account_to);
+        self.errors.required("benefName", &self.benef_name);
+        if let Some(amount) = self.amount {
+            self.errors.amount(amount, Currency::Eur);
+        }
+        let order = match (
+            self.account_from,
+            self.amount,
+            self.account_to,
+            self.benef_name,
+        ) {
+            (Some(account_from), Some(amount), Some(account_to), Some(benef_name)) => {
+                Some(T2Transaction {
+                    account_from,
+                    currency: Currency::Eur,
+                    amount,
+                    account_to,
+                    bic: self.bic,
+                    ks: self.ks,
+                    vs: self.vs,
+                    ss: self.ss,
+                    date: self.date.unwrap_or_else(today).to_string(),
+                    benef_name,
+                    benef_street: self.benef_street,
+                    benef_city: self.benef_city,
+                    benef_country: self.benef_country,
+                    remittance_info1: self.remittance_info1,
+                    remittance_info2: self.remittance_info2,
+                    remittance_info3: self.remittance_info3,
+                    comment: self.comment,
+                    payment_reason: self.payment_reason,
+                    payment_type: Some(self.payment_type.unwrap_or(EuroPaymentType::Standard)),
+                })
+            }
+            _ => None,
+        };
+        self.errors.into_result(order)
+    }
+}
+
+/// Builder of [`ForeignTransaction`]
+///
+/// Mandatory: [`account_from`](Self::account_from),
+/// [`currency`](Self::currency), [`amount`](Self::amount),
+/// [`account_to`](Self::account_to), [`bic`](Self::bic), the beneficiary name,
+/// street, city and country, [`remittance_info1`](Self::remittance_info1),
+/// [`payment_reason`](Self::payment_reason) and
+/// [`details_of_charges`](Self::details_of_charges). The date defaults to today.
+#[derive(Debug, Default)]
+pub struct ForeignTransactionBuilder {
+    errors: Errors,
+    account_from: Option<String>,
+    currency: Option<Currency>,
+    amount: Option<Decimal>,
+    account_to: Option<String>,
+    bic: Option<Bic>,
+    date: Option<NaiveDate>,
+    benef_name: Option<String>,
+    benef_street: Option<String>,
+    benef_city: Option<String>,
+    benef_country: Option<String>,
+    remittance_info1: Option<String>,
+    remittance_info2: Option<String>,
+    remittance_info3: Option<String>,
+    remittance_info4: Option<String>,
+    comment: Option<String>,
+    payment_reason: Option<PaymentReason>,
+    details_of_charges: Option<DetailsOfCharges>,
+}
+
+impl ForeignTransaction {
+    /// Create a builder checking every field
+    #[must_use]
+    pub fn builder() -> ForeignTransactionBuilder {
+        ForeignTransactionBuilder::default()
+    }
+}
+
+impl ForeignTransactionBuilder {
+    common_setters!();
+
+    /// Currency from the ISO 4217 catalogue
+    #[must_use]
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = self.errors.currency(currency, None);
+        self
+    }
+
+    /// Recipient account number or IBAN, up to 34 letters and digits
+    #[must_use]
+    pub fn account_to(mut self, account: &str) -> Self {
+        let account: String = account.chars().filter(|c| !c.is_whitespace()).collect();
+        if account.is_empty()
+            || account.len() > 34
+            || !account.bytes().all(|b| b.is_ascii_alphanumeric())
+        {
+            self.errors
+                .push("accountTo", "must be up to 34 letters and digits");
+            self.account_to = None;
+        } else {
+            self.errors.clear("accountTo");
+            self.account_to = Some(account);
+        }
+        self
+    }
+
+    parse_setter!(
+        /// Recipient bank BIC
+        bic,
+        "bic"
+    );
+    text_setter!(
+        /// Beneficiary name, up to 35 characters
+        benef_name,
+        "benefName",
+        35,
+        true
+    );
+    text_setter!(
+        /// Beneficiary street, up to 35 characters
+        benef_street,
+        "benefStreet",
+        35,
+        true
+    );
+    text_setter!(
+        /// Beneficiary city, up to 35 characters
+        benef_city,
+        "benefCity",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 1, up to 35 characters
+        remittance_info1,
+        "remittanceInfo1",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 2, up to 35 characters
+        remittance_info2,
+        "remittanceInfo2",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 3, up to 35 characters
+        remittance_info3,
+        "remittanceInfo3",
+        35,
+        true
+    );
+    text_setter!(
+        /// Remittance info line 4, up to 35 characters
+        remittance_info4,
+        "remittanceInfo4",
+        35,
+        true
+    );
+
+    /// Beneficiary country as two letter ISO 3166 code
+    #[must_use]
+    pub fn benef_country(mut self, country: &str) -> Self {
+        self.benef_country = self.errors.country("benefCountry", country);
+        self
+    }
+
+    /// Payment reason
+    #[must_use]
+    pub fn payment_reason(mut self, reason: PaymentReason) -> Self {
+        self.payment_reason = self.errors.payment_reason(reason);
+        self
+    }
+
+    /// Who pays the charges
+    #[must_use]
+    pub const fn details_of_charges(mut self, details: DetailsOfCharges) -> Self {
+        self.details_of_charges = Some(details);
+        self
+    }
+
+    /// Build the order
+    /// # Errors
+    /// * `Error::InvalidFields` - Invalid values or missing mandatory fields
+    pub fn build(mut self) -> Result<ForeignTransaction, Error> {
+        let errors = &mut self.errors;
+        errors.required("accountFrom", &self.account_from);
+        errors.required("currency", &self.currency);
+        errors.required("amount", &self.amount);
+        errors.required("accountTo", &self.account_to);
+        errors.required("bic", &self.bic);
+        errors.required("benefName", &self.benef_name);
+        errors.required("benefStreet", &self.benef_street);
+        errors.required("benefCity", &self.benef_city);
+        errors.required("benefCountry", &self.benef_country);
+        errors.required("remittanceInfo1", &self.remittance_info1);
+        errors.required("paymentReason", &self.payment_reason);
+        errors.required("detailsOfCharges", &self.details_of_charges);
+        if let (Some(amount), Some(currency)) = (self.amount, self.currency) {
+            errors.amount(amount, currency);
+        }
+        let order = (|| {
+            Some(ForeignTransaction {
+                account_from: self.account_from?,
+                currency: self.currency?,
+                amount: self.amount?,
+                account_to: self.account_to?,
+                bic: self.bic?,
+                date: self.date.unwrap_or_else(today).to_string(),
+                benef_name: self.benef_name?,
+                benef_street: self.benef_street?,
+                benef_city: self.benef_city?,
+                benef_country: self.benef_country?,
+                remittance_info1: self.remittance_info1?,
+                remittance_info2: self.remittance_info2,
+                remittance_info3: self.remittance_info3,
+                remittance_info4: self.remittance_info4,
+                comment: self.comment,
+                payment_reason: self.payment_reason?,
+                details_of_charges: self.details_of_charges?,
+            })
+        })();
+        self.errors.into_result(order)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::transaction::{Import, Type};
+
+    fn fields(result: Result<impl std::fmt::Debug, Error>) -> Vec<&'static str> {
+        match result {
+            Err(Error::InvalidFields(errors)) => errors.iter().map(|e| e.field).collect(),
+            other => panic!("expected field errors, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn domestic_defaults() {
+        let order = DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(100, 0))
+            .account_to("2000145399/0800")
+            .build()
+            .unwrap();
+        assert_eq!(order.currency, Currency::Czk);
+        assert_eq!(order.account_to, "2000145399");
+        assert_eq!(order.date, today().to_string());
+        assert_eq!(order.payment_type, Some(DomesticPaymentType::Standard));
+        assert_eq!(order.vs, None);
+    }
+
+    #[test]
+    fn domestic_field_errors() {
+        let result = DomesticTransaction::builder()
+            .account_from("2101179628")
+            .amount(Decimal::new(1005, 3))
+            .vs("12345678901")
+            .date("2025-13-01")
+            .message_for_recipient(&"x".repeat(141))
+            .build();
+        assert_eq!(
+            fields(result),
+            vec![
+                "accountFrom",
+                "vs",
+                "date",
+                "messageForRecipient",
+                "accountTo",
+                "amount"
+            ]
+        );
+    }
+
+    #[test]
+    fn later_valid_value_clears_error() {
+        let order = DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(100, 0))
+            .account_to("2000145399/9999")
+            .account_to("2000145399/0800")
+            .build();
+        assert!(order.is_ok());
+    }
+
+    #[test]
+    fn euro_builder() {
+        let order = T2Transaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(2500, 2))
+            .account_to("DE89 3704 0044 0532 0130 00")
+            .benef_name("Hans Muller")
+            .benef_country("DE")
+            .date("2030-01-31")
+            .build()
+            .unwrap();
+        assert_eq!(order.currency, Currency::Eur);
+        assert_eq!(order.date, "2030-01-31");
+        assert_eq!(order.payment_type, Some(EuroPaymentType::Standard));
+
+        let result = T2Transaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(2500, 2))
+            .account_to("DE00370400440532013000")
+            .benef_name("Hans Müller")
+            .build();
+        assert_eq!(fields(result), vec!["accountTo", "benefName"]);
+    }
+
+    #[test]
+    fn foreign_builder() {
+        let builder = ForeignTransaction::builder()
+            .account_from("2101179627")
+            .currency(Currency::Usd)
+            .amount(Decimal::new(250, 0))
+            .account_to("PK36SCBL0000001123456702")
+            .bic("ALFHPKKAXXX")
+            .benef_name("Corp")
+            .benef_street("Street 13")
+            .benef_city("Karachi")
+            .benef_country("PK")
+            .remittance_info1("Payment");
+        assert_eq!(
+            fields(builder.build()),
+            vec!["paymentReason", "detailsOfCharges"]
+        );
+        let order = ForeignTransaction::builder()
+            .account_from("2101179627")
+            .currency(Currency::Jpy)
+            .amount(Decimal::new(1000, 0))
+            .account_to("123456789")
+            .bic("MHCBJPJT")
+            .benef_name("K.K.")
+            .benef_street("Chiyoda 1")
+            .benef_city("Tokyo")
+            .benef_country("JP")
+            .remittance_info1("Invoice 5")
+            .payment_reason(PaymentReason::ExportImportGoods)
+            .details_of_charges(DetailsOfCharges::Shared)
+            .build()
+            .unwrap();
+        assert_eq!(order.currency, Currency::Jpy);
+    }
+
+    #[test]
+    fn builders_reject_what_validate_rejects() {
+        let result = DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(100, 0))
+            .account_to("2000145399/0800")
+            .currency(Currency::Eur)
+            .payment_reason(PaymentReason::from_code(1))
+            .date("2020-01-01")
+            .build();
+        assert_eq!(fields(result), vec!["currency", "paymentReason", "date"]);
+
+        let result = T2Transaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(2500, 2))
+            .account_to("DE89370400440532013000")
+            .benef_name("Hans Muller")
+            .payment_reason(PaymentReason::from_code(1000))
+            .build();
+        assert_eq!(fields(result), vec!["paymentReason"]);
+
+        let builder = ForeignTransaction::builder().currency("XYZ".parse().unwrap());
+        assert!(builder
+            .currency(Currency::Usd)
+            .errors
+            .0
+            .iter()
+            .all(|e| e.field != "currency"));
+        let result = ForeignTransaction::builder()
+            .currency("XYZ".parse().unwrap())
+            .build();
+        assert!(fields(result).contains(&"currency"));
+
+        let order = DomesticTransaction::builder()
+            .account_from("2101179627")
+            .amount(Decimal::new(100, 0))
+            .account_to("2000145399/0800")
+            .currency(Currency::Czk)
+            .payment_reason(PaymentReason::ExportImportGoods)
+            .build()
+            .unwrap();
+        let import = Import {
+            orders: vec![Type::Domestic(order)],
+        };
+        assert!(import.validate().is_empty());
+    }
+}
@@ -1,10 +1,28 @@
 //! Types for transaction import
+mod batch;
+mod builder;
+mod validate;
 mod xml;
 
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::error::Error;
+use crate::types::account_number::CzAccountNumber;
+use crate::types::bic::Bic;
+use crate::types::currency::{Currency, Money};
+use crate::types::iban::Iban;
+use crate::types::payment_reason::PaymentReason;
+use crate::types::symbol::{ConstantSymbol, SpecificSymbol, VariableSymbol};
+
+pub use batch::{Batch, BatchImportReport, BatchLimits, OrderResult, OrderStatus};
+pub use builder::{DomesticTransactionBuilder, ForeignTransactionBuilder, T2TransactionBuilder};
+pub use validate::Diagnostic;
+
+/// Bank code of Fio banka, the bank of every `accountFrom`
+const FIO_BANK_CODE: &str = "2010";
+
 /// Details of charges for foreign transactions
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub enum DetailsOfCharges {
@@ -84,7 +102,7 @@ pub struct DomesticTransaction {
     /// Account from
     pub account_from: String,
     /// Currency
-    pub currency: String,
+    pub currency: Currency,
     /// Amount
     pub amount: Decimal,
     /// Account to
@@ -93,13 +111,13 @@ pub struct DomesticTransaction {
     pub bank_code: String,
     /// Constant symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ks: Option<String>,
+    pub ks: Option<ConstantSymbol>,
     /// Variable symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub vs: Option<String>,
+    pub vs: Option<VariableSymbol>,
     /// Specific symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ss: Option<String>,
+    pub ss: Option<SpecificSymbol>,
     /// Date of transaction
     pub date: String,
     /// Message for recipient
@@ -110,12 +128,42 @@ pub struct DomesticTransaction {
     pub comment: Option<String>,
     /// Payment reason
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub payment_reason: Option<String>,
+    pub payment_reason: Option<PaymentReason>,
     /// Payment type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_type: Option<DomesticPaymentType>,
 }
 
+impl DomesticTransaction {
+    /// Amount in the order currency
+    #[must_use]
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+
+    /// Set recipient account and bank code from a validated account number
+    #[must_use]
+    pub fn with_account_to(mut self, account: &CzAccountNumber) -> Self {
+        self.account_to = account.account();
+        self.bank_code = account.bank_code().to_string();
+        self
+    }
+
+    /// Recipient account parsed from `account_to` and `bank_code`
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - Invalid length or checksum
+    pub fn account_to_parsed(&self) -> Result<CzAccountNumber, Error> {
+        CzAccountNumber::parse_with_bank(&self.account_to, &self.bank_code)
+    }
+
+    /// Sender's Fio account parsed from `account_from`
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - Invalid length or checksum
+    pub fn account_from_parsed(&self) -> Result<CzAccountNumber, Error> {
+        CzAccountNumber::parse_with_bank(&self.account_from, FIO_BANK_CODE)
+    }
+}
+
 /// Euro (T2) transaction data
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -123,23 +171,23 @@ pub struct T2Transaction {
     /// Account from
     pub account_from: String,
     /// Currency
-    pub currency: String,
+    pub currency: Currency,
     /// Amount
     pub amount: Decimal,
     /// Account to
-    pub account_to: String,
+    pub account_to: Iban,
     /// BIC
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub bic: Option<String>,
+    pub bic: Option<Bic>,
     /// Constant symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ks: Option<String>,
+    pub ks: Option<ConstantSymbol>,
     /// Variable symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub vs: Option<String>,
+    pub vs: Option<VariableSymbol>,
     /// Specific symbol
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub ss: Option<String>,
+    pub ss: Option<SpecificSymbol>,
     /// Date of transaction
     pub date: String,
     /// Beneficiary name
@@ -167,7 +215,7 @@ pub struct T2Transaction {
     pub comment: Option<String>,
     /// Payment reason
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub payment_reason: Option<String>,
+    pub payment_reason: Option<PaymentReason>,
     /// Payment type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_type: Option<EuroPaymentType>,
@@ -180,13 +228,13 @@ pub struct ForeignTransaction {
     /// Account from
     pub account_from: String,
     /// Currency
-    pub currency: String,
+    pub currency: Currency,
     /// Amount
     pub amount: Decimal,
     /// Account to
     pub account_to: String,
     /// BIC (mandatory for foreign transactions)
-    pub bic: String,
+    pub bic: Bic,
     /// Date of transaction
     pub date: String,
     /// Beneficiary name
@@ -212,11 +260,27 @@ pub struct ForeignTransaction {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
     /// Payment reason
-    pub payment_reason: String,
+    pub payment_reason: PaymentReason,
     /// Details of charges
     pub details_of_charges: DetailsOfCharges,
 }
 
+impl T2Transaction {
+    /// Amount in the order currency
+    #[must_use]
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
+impl ForeignTransaction {
+    /// Amount in the order currency
+    #[must_use]
+    pub fn money(&self) -> Money {
+        Money::new(self.amount, self.currency)
+    }
+}
+
 /// Transaction type
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub enum Type {
@@ -229,7 +293,7 @@ pub enum Type {
 }
 
 /// Import transactions
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Import {
     /// List of transaction orders
@@ -279,6 +343,19 @@ impl ImportBuilder {
         self
     }
 
+    /// Add domestic transaction after validating its account numbers
+    /// # Errors
+    /// * `Error::InvalidAccountNumber` - `account_from`, `account_to` or
+    ///   `bank_code` is not a valid Czech account number
+    /// * `Error::UnknownBankCode` - `bank_code` is not in the ČNB bank code list
+    pub fn try_domestic(&mut self, transaction: DomesticTransaction) -> Result<&mut Self, Error> {
+        transaction.account_from_parsed()?;
+        if transaction.account_to_parsed()?.bank().is_none() {
+            return Err(Error::UnknownBankCode(transaction.bank_code));
+        }
+        Ok(self.domestic(transaction))
+    }
+
     /// Add Euro transaction
     pub fn euro(&mut self, transaction: T2Transaction) -> &mut Self {
         self.euro.push(transaction);
@@ -291,6 +368,18 @@ impl ImportBuilder {
         self
     }
 
+    /// Add foreign transaction after validating its payment reason
+    /// # Errors
+    /// * `Error::InvalidPaymentReason` - `payment_reason` is not a three digit code
+    pub fn try_foreign(&mut self, transaction: ForeignTransaction) -> Result<&mut Self, Error> {
+        if !transaction.payment_reason.is_valid() {
+            return Err(Error::InvalidPaymentReason(
+                transaction.payment_reason.to_string(),
+            ));
+        }
+        Ok(self.foreign(transaction))
+    }
+
     /// Build import
     pub fn build(&mut self) -> Import {
         let mut import = Import::new();
@@ -321,10 +410,10 @@ mod tests {
     fn sample_foreign() -> ForeignTransaction {
         ForeignTransaction {
             account_from: "CZ123".to_string(),
-            currency: "USD".to_string(),
+            currency: Currency::Usd,
             amount: Decimal::new(25000, 2),
             account_to: "US456".to_string(),
-            bic: "ALFHPKKAXXX".to_string(),
+            bic: "ALFHPKKAXXX".parse().unwrap(),
             date: "2024-03-15".to_string(),
             benef_name: "Corp".to_string(),
             benef_street: "St 13".to_string(),
@@ -335,7 +424,7 @@ mod tests {
             remittance_info3: None,
             remittance_info4: None,
             comment: None,
-            payment_reason: "110".to_string(),
+            payment_reason: PaymentReason::ExportImportGoods,
             details_of_charges: DetailsOfCharges::Shared,
         }
     }
@@ -349,7 +438,7 @@ mod tests {
     fn builder_domestic() {
         let t = DomesticTransaction {
             account_from: "1".into(),
-            currency: "CZK".into(),
+            currency: Currency::Czk,
             amount: Decimal::new(100, 0),
             account_to: "2".into(),
             bank_code: "0800".into(),
@@ -366,6 +455,42 @@ mod tests {
         assert_eq!(import.orders.len(), 1);
     }
 
+    #[test]
+    fn builder_try_domestic_validates_accounts() {
+        let t = DomesticTransaction {
+            account_from: "2101179627".into(),
+            currency: Currency::Czk,
+            amount: Decimal::new(100, 0),
+            account_to: "2".into(),
+            bank_code: "0800".into(),
+            ks: None,
+            vs: None,
+            ss: None,
+            date: "2024-01-01".into(),
+            message_for_recipient: None,
+            comment: None,
+            payment_reason: None,
+            payment_type: None,
+        };
+        let mut builder = ImportBuilder::new();
+        assert!(matches!(
+            builder.try_domestic(t.clone()),
+            Err(Error::InvalidAccountNumber(_))
+        ));
+        let account: CzAccountNumber = "19-2000145399/0800".parse().unwrap();
+        let t = t.with_account_to(&account);
+        assert_eq!(t.account_to, "19-2000145399");
+        assert_eq!(t.bank_code, "0800");
+        assert_eq!(t.account_to_parsed().unwrap(), account);
+        let mut unknown = t.clone();
+        unknown.bank_code = "9999".into();
+        assert!(matches!(
+            builder.try_domestic(unknown),
+            Err(Error::UnknownBankCode(_))
+        ));
+        assert_eq!(builder.try_domestic(t).unwrap().build().orders.len(), 1);
+    }
+
     #[test]
     fn builder_foreign() {
         let import = ImportBuilder::new().foreign(sample_foreign()).build();
@@ -373,6 +498,20 @@ mod tests {
         assert!(matches!(import.orders[0], Type::Foreign(_)));
     }
 
+    #[test]
+    fn builder_try_foreign_validates_payment_reason() {
+        let mut t = sample_foreign();
+        t.payment_reason = PaymentReason::from_code(12);
+        let mut builder = ImportBuilder::new();
+        assert!(matches!(
+            builder.try_foreign(t),
+            Err(Error::InvalidPaymentReason(_))
+        ));
+        let mut t = sample_foreign();
+        t.payment_reason = PaymentReason::from_code(999);
+        assert_eq!(builder.try_foreign(t).unwrap().build().orders.len(), 1);
+    }
+
     #[test]
     fn builder_default_empty() {
         assert!(Import::default().orders.is_empty());
@@ -0,0 +1,378 @@
+//! Pre-submission validation of import orders against the Fio import rules
+use chrono::{Local, NaiveDate};
+use rust_decimal::Decimal;
+use std::fmt;
+
+use super::{
+    DomesticPaymentType, DomesticTransaction, ForeignTransaction, Import, T2Transaction, Type,
+    FIO_BANK_CODE,
+};
+use crate::types::account_number::CzAccountNumber;
+use crate::types::currency::{Currency, Money};
+use crate::types::payment_reason::PaymentReason;
+
+/// Special characters allowed in SEPA text fields besides letters, digits and
+/// space (SEPA basic Latin character set)
+const SEPA_SPECIAL_CHARACTERS: &str = "/-?:().,'+";
+
+/// Letters with diacritics allowed in domestic text fields besides printable
+/// ASCII (Czech and Slovak alphabets)
+const DOMESTIC_LETTERS: &str = "áäčďéěíĺľňóôŕřšťúůýžÁÄČĎÉĚÍĹĽŇÓÔŔŘŠŤÚŮÝŽ";
+
+/// Problem found in one field of one order
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// Index of the order in [`Import::orders`]
+    pub index: usize,
+    /// Field name as used in the import XML
+    pub field: &'static str,
+    /// Human readable description
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "order {}: {}: {}", self.index, self.field, self.message)
+    }
+}
+
+impl Import {
+    /// Check all orders against the documented import rules
+    ///
+    /// Catches problems the bank would otherwise only report after
+    /// [`crate::Fio::import_transactions`]. An empty result means no problem
+    /// was found, not that the bank will accept the orders.
+    /// # Returns
+    /// * `Vec<Diagnostic>` - Problems in order of the orders and their fields
+    #[must_use]
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        self.validate_at(Local::now().date_naive())
+    }
+
+    /// Same as [`Import::validate`], with a fixed current date
+    /// # Arguments
+    /// * `today` - Date before which order dates are rejected as past
+    #[must_use]
+    pub fn validate_at(&self, today: NaiveDate) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for (index, order) in self.orders.iter().enumerate() {
+            let mut checker = Checker {
+                index,
+                today,
+                diagnostics: &mut diagnostics,
+            };
+            match order {
+                Type::Domestic(t) => checker.domestic(t),
+                Type::Euro(t) => checker.euro(t),
+                Type::Foreign(t) => checker.foreign(t),
+            }
+        }
+        diagnostics
+    }
+}
+
+struct Checker<'a> {
+    index: usize,
+    today: NaiveDate,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn domestic(&mut self, t: &DomesticTransaction) {
+        self.account_from(&t.account_from);
+        if t.currency != Currency::Czk {
+            self.report("currency", "domestic payments must be in CZK");
+        }
+        self.amount(t.amount, t.currency);
+        match CzAccountNumber::parse_with_bank(&t.account_to, &t.bank_code) {
+            Ok(account) if account.bank().is_none() => {
+                self.report("bankCode", format!("unknown bank code {}", t.bank_code));
+            }
+            Ok(_) => {}
+            Err(e) => self.report("accountTo", e.to_string()),
+        }
+        if t.payment_type == Some(DomesticPaymentType::DirectDebit) && t.ks.is_none() {
+            // Fio requires a constant symbol to pair collections with their mandate
+            self.report("ks", "direct debit orders need a constant symbol");
+        }
+        self.date(&t.date);
+        self.text(
+            "messageForRecipient",
+            t.message_for_recipient.as_deref(),
+            140,
+            false,
+        );
+        self.text("comment", t.comment.as_deref(), 255, false);
+        self.payment_reason(t.payment_reason);
+    }
+
+    fn euro(&mut self, t: &T2Transaction) {
+        self.account_from(&t.account_from);
+        if t.currency != Currency::Eur {
+            self.report("currency", "euro payments must be in EUR");
+        }
+        self.amount(t.amount, t.currency);
+        self.date(&t.date);
+        self.required("benefName", &t.benef_name);
+        self.text("benefName", Some(&t.benef_name), 35, true);
+        self.text("benefStreet", t.benef_street.as_deref(), 35, true);
+        self.text("benefCity", t.benef_city.as_deref(), 35, true);
+        self.country("benefCountry", t.benef_country.as_deref());
+        self.text("remittanceInfo1", t.remittance_info1.as_deref(), 35, true);
+        self.text("remittanceInfo2", t.remittance_info2.as_deref(), 35, true);
+        self.text("remittanceInfo3", t.remittance_info3.as_deref(), 35, true);
+        self.text("comment", t.comment.as_deref(), 255, false);
+        self.payment_reason(t.payment_reason);
+    }
+
+    fn foreign(&mut self, t: &ForeignTransaction) {
+        self.account_from(&t.account_from);
+        self.amount(t.amount, t.currency);
+        self.required("accountTo", &t.account_to);
+        if t.account_to.len() > 34 || !t.account_to.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            self.report("accountTo", "must be up to 34 letters and digits");
+        }
+        self.date(&t.date);
+        self.required("benefName", &t.benef_name);
+        self.text("benefName", Some(&t.benef_name), 35, true);
+        self.required("benefStreet", &t.benef_street);
+        self.text("benefStreet", Some(&t.benef_street), 35, true);
+        self.required("benefCity", &t.benef_city);
+        self.text("benefCity", Some(&t.benef_city), 35, true);
+        self.country("benefCountry", Some(&t.benef_country));
+        self.required("remittanceInfo1", &t.remittance_info1);
+        self.text("remittanceInfo1", Some(&t.remittance_info1), 35, true);
+        self.text("remittanceInfo2", t.remittance_info2.as_deref(), 35, true);
+        self.text("remittanceInfo3", t.remittance_info3.as_deref(), 35, true);
+        self.text("remittanceInfo4", t.remittance_info4.as_deref(), 35, true);
+        self.text("comment", t.comment.as_deref(), 255, false);
+        self.payment_reason(Some(t.payment_reason));
+    }
+
+    fn report(&mut self, field: &'static str, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            index: self.index,
+            field,
+            message: message.into(),
+        });
+    }
+
+    fn account_from(&mut self, account: &str) {
+        if let Err(e) = CzAccountNumber::parse_with_bank(account, FIO_BANK_CODE) {
+            self.report("accountFrom", e.to_string());
+        }
+    }
+
+    fn amount(&mut self, amount: Decimal, currency: Currency) {
+        if !currency.is_known() {
+            self.report("currency", format!("unknown currency {currency}"));
+        }
+        if amount <= Decimal::ZERO {
+            self.report("amount", "must be greater than zero");
+        }
+        if let Err(e) = Money::exact(amount, currency) {
+            self.report("amount", e.to_string());
+        }
+    }
+
+    fn date(&mut self, date: &str) {
+        match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+            Ok(date) if date < self.today => self.report("date", "must not be in the past"),
+            Ok(_) => {}
+            Err(_) => self.report("date", "must be a valid YYYY-MM-DD date"),
+        }
+    }
+
+    fn required(&mut self, field: &'static str, value: &str) {
+        if value.trim().is_empty() {
+            self.report(field, "is required");
+        }
+    }
+
+    fn text(&mut self, field: &'static str, value: Option<&str>, max_len: usize, sepa: bool) {
+        if let Some(problem) = value.and_then(|value| text_problem(value, max_len, sepa)) {
+            self.report(field, problem);
+        }
+    }
+
+    fn country(&mut self, field: &'static str, value: Option<&str>) {
+        let Some(value) = value else { return };
+        if value.len() != 2 || !value.bytes().all(|b| b.is_ascii_uppercase()) {
+            self.report(field, "must be a two letter ISO 3166 code");
+        }
+    }
+
+    fn payment_reason(&mut self, value: Option<PaymentReason>) {
+        if value.is_some_and(|reason| !reason.is_valid()) {
+            self.report("paymentReason", "must be a three digit code");
+        }
+    }
+}
+
+/// First problem of a text field: too long or containing a character
+/// outside the allowed set
+///
+/// SEPA fields allow the SEPA basic Latin set only. Domestic fields allow
+/// printable ASCII characters and Czech and Slovak letters with diacritics.
+pub(super) fn text_problem(value: &str, max_len: usize, sepa: bool) -> Option<String> {
+    let length = value.chars().count();
+    if length > max_len {
+        return Some(format!(
+            "is {length} characters long, at most {max_len} allowed"
+        ));
+    }
+    let allowed = |c: char| {
+        if sepa {
+            c == ' ' || c.is_ascii_alphanumeric() || SEPA_SPECIAL_CHARACTERS.contains(c)
+        } else {
+            (' '..='~').contains(&c) || DOMESTIC_LETTERS.contains(c)
+        }
+    };
+    value
+        .chars()
+        .find(|&c| !allowed(c))
+        .map(|c| format!("contains disallowed character {c:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{domestic, euro};
+    use crate::types::transaction::{DetailsOfCharges, DomesticPaymentType};
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+    }
+
+    fn foreign() -> ForeignTransaction {
+        ForeignTransaction {
+            account_from: "2101179627".into(),
+            currency: Currency::Usd,
+            amount: Decimal::new(250, 0),
+            account_to: "PK36SCBL0000001123456702".into(),
+            bic: "ALFHPKKAXXX".parse().unwrap(),
+            date: "2025-03-01".into(),
+            benef_name: "Corp".into(),
+            benef_street: "Street 13".into(),
+            benef_city: "Karachi".into(),
+            benef_country: "PK".into(),
+            remittance_info1: "Payment".into(),
+            remittance_info2: None,
+            remittance_info3: None,
+            remittance_info4: None,
+            comment: None,
+            payment_reason: PaymentReason::ExportImportGoods,
+            details_of_charges: DetailsOfCharges::Shared,
+        }
+    }
+
+    fn fields(diagnostics: &[Diagnostic]) -> Vec<(usize, &'static str)> {
+        diagnostics.iter().map(|d| (d.index, d.field)).collect()
+    }
+
+    #[test]
+    fn valid_orders_pass() {
+        let import = Import {
+            orders: vec![
+                Type::Domestic(domestic(100)),
+                Type::Euro(euro(100)),
+                Type::Foreign(foreign()),
+            ],
+        };
+        assert_eq!(import.validate_at(today()), vec![]);
+    }
+
+    #[test]
+    fn domestic_rules() {
+        let mut t = domestic(100);
+        t.currency = Currency::Eur;
+        t.amount = Decimal::new(1005, 3);
+        t.bank_code = "9999".into();
+        t.payment_type = Some(DomesticPaymentType::DirectDebit);
+        t.ks = None;
+        t.date = "2025-02-28".into();
+        t.message_for_recipient = Some("x".repeat(141));
+        t.comment = Some("50 €".into());
+        let import = Import {
+            orders: vec![Type::Domestic(domestic(100)), Type::Domestic(t)],
+        };
+        assert_eq!(
+            fields(&import.validate_at(today())),
+            vec![
+                (1, "currency"),
+                (1, "amount"),
+                (1, "bankCode"),
+                (1, "ks"),
+                (1, "date"),
+                (1, "messageForRecipient"),
+                (1, "comment"),
+            ]
+        );
+    }
+
+    #[test]
+    fn domestic_character_set() {
+        let mut t = domestic(100);
+        t.message_for_recipient = Some("Díky! Tom & Jerry, info@fio.cz, ref_12 #3".into());
+        t.comment = Some("<faktura> \"2025\" [50%]".into());
+        let import = Import {
+            orders: vec![Type::Domestic(t)],
+        };
+        assert_eq!(import.validate_at(today()), vec![]);
+        assert!(text_problem("tab\there", 140, false).is_some());
+        assert!(text_problem("Žluťoučký kůň, Ľubomír Ôsmy", 140, false).is_none());
+        for foreign in ["Привет", "付款", "Łódź", "50 €"] {
+            assert!(text_problem(foreign, 140, false).is_some(), "{foreign}");
+        }
+        assert!(text_problem("a&b", 35, true).is_some());
+    }
+
+    #[test]
+    fn euro_rules() {
+        let mut t = euro(100);
+        t.currency = Currency::Czk;
+        t.amount = Decimal::ZERO;
+        t.benef_name = "Hans Müller".into();
+        t.benef_country = Some("Germany".into());
+        t.date = "tomorrow".into();
+        let import = Import {
+            orders: vec![Type::Euro(t)],
+        };
+        assert_eq!(
+            fields(&import.validate_at(today())),
+            vec![
+                (0, "currency"),
+                (0, "amount"),
+                (0, "date"),
+                (0, "benefName"),
+                (0, "benefCountry"),
+            ]
+        );
+    }
+
+    #[test]
+    fn foreign_rules() {
+        let mut t = foreign();
+        t.account_from = "124".into();
+        t.benef_street = " ".into();
+        t.payment_reason = PaymentReason::from_code(1);
+        let mut unknown = foreign();
+        unknown.currency = "XYZ".parse().unwrap();
+        let import = Import {
+            orders: vec![Type::Foreign(t), Type::Foreign(unknown)],
+        };
+        let diagnostics = import.validate_at(today());
+        assert_eq!(
+            fields(&diagnostics),
+            vec![
+                (0, "accountFrom"),
+                (0, "benefStreet"),
+                (0, "paymentReason"),
+                (1, "currency")
+            ]
+        );
+        assert!(diagnostics[2]
+            .to_string()
+            .starts_with("order 0: paymentReason"));
+    }
+}
@@ -1,5 +1,7 @@
 //! XML generation for transaction import
 
+use std::fmt::Display;
+
 use super::{DomesticTransaction, ForeignTransaction, Import, T2Transaction, Type};
 
 impl Import {
@@ -31,123 +33,131 @@ impl Import {
     }
 }
 
+/// Element with its text escaped, so no field can close the element or
+/// inject markup
+fn element(tag: &str, value: &impl Display) -> String {
+    let mut text = String::new();
+    for c in value.to_string().chars() {
+        match c {
+            '&' => text.push_str("&amp;"),
+            '<' => text.push_str("&lt;"),
+            '>' => text.push_str("&gt;"),
+            '"' => text.push_str("&quot;"),
+            '\'' => text.push_str("&apos;"),
+            c => text.push(c),
+        }
+    }
+    format!("<{tag}>{text}</{tag}>")
+}
+
 fn convert_foreign(result: &mut Vec<String>, t: &ForeignTransaction) {
     result.push("<ForeignTransaction>".to_string());
-    result.push(format!("<accountFrom>{}</accountFrom>", t.account_from));
-    result.push(format!("<currency>{}</currency>", t.currency));
-    result.push(format!("<amount>{}</amount>", t.amount));
-    result.push(format!("<accountTo>{}</accountTo>", t.account_to));
-    result.push(format!("<bic>{}</bic>", t.bic));
-    result.push(format!("<date>{}</date>", t.date));
-    result.push(format!("<benefName>{}</benefName>", t.benef_name));
-    result.push(format!("<benefStreet>{}</benefStreet>", t.benef_street));
-    result.push(format!("<benefCity>{}</benefCity>", t.benef_city));
-    result.push(format!("<benefCountry>{}</benefCountry>", t.benef_country));
-    result.push(format!(
-        "<remittanceInfo1>{}</remittanceInfo1>",
-        t.remittance_info1
-    ));
+    result.push(element("accountFrom", &t.account_from));
+    result.push(element("currency", &t.currency));
+    result.push(element("amount", &t.amount));
+    result.push(element("accountTo", &t.account_to));
+    result.push(element("bic", &t.bic));
+    result.push(element("date", &t.date));
+    result.push(element("benefName", &t.benef_name));
+    result.push(element("benefStreet", &t.benef_street));
+    result.push(element("benefCity", &t.benef_city));
+    result.push(element("benefCountry", &t.benef_country));
+    result.push(element("remittanceInfo1", &t.remittance_info1));
     if let Some(v) = &t.remittance_info2 {
-        result.push(format!("<remittanceInfo2>{v}</remittanceInfo2>"));
+        result.push(element("remittanceInfo2", v));
     }
     if let Some(v) = &t.remittance_info3 {
-        result.push(format!("<remittanceInfo3>{v}</remittanceInfo3>"));
+        result.push(element("remittanceInfo3", v));
     }
     if let Some(v) = &t.remittance_info4 {
-        result.push(format!("<remittanceInfo4>{v}</remittanceInfo4>"));
+        result.push(element("remittanceInfo4", v));
     }
     if let Some(v) = &t.comment {
-        result.push(format!("<comment>{v}</comment>"));
+        result.push(element("comment", v));
     }
-    result.push(format!(
-        "<paymentReason>{}</paymentReason>",
-        t.payment_reason
-    ));
-    result.push(format!(
-        "<detailsOfCharges>{}</detailsOfCharges>",
-        t.details_of_charges
-    ));
+    result.push(element("paymentReason", &t.payment_reason));
+    result.push(element("detailsOfCharges", &t.details_of_charges));
     result.push("</ForeignTransaction>".to_string());
 }
 
 fn convert_euro(result: &mut Vec<String>, t: &T2Transaction) {
     result.push("<T2Transaction>".to_string());
-    result.push(format!("<accountFrom>{}</accountFrom>", t.account_from));
-    result.push(format!("<currency>{}</currency>", t.currency));
-    result.push(format!("<amount>{}</amount>", t.amount));
-    result.push(format!("<accountTo>{}</accountTo>", t.account_to));
+    result.push(element("accountFrom", &t.account_from));
+    result.push(element("currency", &t.currency));
+    result.push(element("amount", &t.amount));
+    result.push(element("accountTo", &t.account_to));
     if let Some(v) = &t.bic {
-        result.push(format!("<bic>{v}</bic>"));
+        result.push(element("bic", v));
     }
     if let Some(v) = &t.ks {
-        result.push(format!("<ks>{v}</ks>"));
+        result.push(element("ks", &v.padded()));
     }
     if let Some(v) = &t.vs {
-        result.push(format!("<vs>{v}</vs>"));
+        result.push(element("vs", v));
     }
     if let Some(v) = &t.ss {
-        result.push(format!("<ss>{v}</ss>"));
+        result.push(element("ss", v));
     }
-    result.push(format!("<date>{}</date>", t.date));
-    result.push(format!("<benefName>{}</benefName>", t.benef_name));
+    result.push(element("date", &t.date));
+    result.push(element("benefName", &t.benef_name));
     if let Some(v) = &t.benef_street {
-        result.push(format!("<benefStreet>{v}</benefStreet>"));
+        result.push(element("benefStreet", v));
     }
     if let Some(v) = &t.benef_city {
-        result.push(format!("<benefCity>{v}</benefCity>"));
+        result.push(element("benefCity", v));
     }
     if let Some(v) = &t.benef_country {
-        result.push(format!("<benefCountry>{v}</benefCountry>"));
+        result.push(element("benefCountry", v));
     }
     if let Some(v) = &t.remittance_info1 {
-        result.push(format!("<remittanceInfo1>{v}</remittanceInfo1>"));
+        result.push(element("remittanceInfo1", v));
     }
     if let Some(v) = &t.remittance_info2 {
-        result.push(format!("<remittanceInfo2>{v}</remittanceInfo2>"));
+        result.push(element("remittanceInfo2", v));
     }
     if let Some(v) = &t.remittance_info3 {
-        result.push(format!("<remittanceInfo3>{v}</remittanceInfo3>"));
+        result.push(element("remittanceInfo3", v));
     }
     if let Some(v) = &t.comment {
-        result.push(format!("<comment>{v}</comment>"));
+        result.push(element("comment", v));
     }
     if let Some(v) = &t.payment_reason {
-        result.push(format!("<paymentReason>{v}</paymentReason>"));
+        result.push(element("paymentReason", v));
     }
     if let Some(v) = &t.payment_type {
-        result.push(format!("<paymentType>{v}</paymentType>"));
+        result.push(element("paymentType", v));
     }
     result.push("</T2Transaction>".to_string());
 }
 
 fn convert_domestic(result: &mut Vec<String>, t: &DomesticTransaction) {
     result.push("<DomesticTransaction>".to_string());
-    result.push(format!("<accountFrom>{}</accountFrom>", t.account_from));
-    result.push(format!("<currency>{}</currency>", t.currency));
-    result.push(format!("<amount>{}</amount>", t.amount));
-    result.push(format!("<accountTo>{}</accountTo>", t.account_to));
-    result.push(format!("<bankCode>{}</bankCode>", t.bank_code));
+    result.push(element("accountFrom", &t.account_from));
+    result.push(element("currency", &t.currency));
+    result.push(element("amount", &t.amount));
+    result.push(element("accountTo", &t.account_to));
+    result.push(element("bankCode", &t.bank_code));
     if let Some(v) = &t.ks {
-        result.push(format!("<ks>{v}</ks>"));
+        result.push(element("ks", &v.padded()));
     }
     if let Some(v) = &t.vs {
-        result.push(format!("<vs>{v}</vs>"));
+        result.push(element("vs", v));
     }
     if let Some(v) = &t.ss {
-        result.push(format!("<ss>{v}</ss>"));
+        result.push(element("ss", v));
     }
-    result.push(format!("<date>{}</date>", t.date));
+    result.push(element("date", &t.date));
     if let Some(v) = &t.message_for_recipient {
-        result.push(format!("<messageForRecipient>{v}</messageForRecipient>"));
+        result.push(element("messageForRecipient", v));
     }
     if let Some(v) = &t.comment {
-        result.push(format!("<comment>{v}</comment>"));
+        result.push(element("comment", v));
     }
     if let Some(v) = &t.payment_reason {
-        result.push(format!("<paymentReason>{v}</paymentReason>"));
+        result.push(element("paymentReason", v));
     }
     if let Some(v) = &t.payment_type {
-        result.push(format!("<paymentType>{v}</paymentType>"));
+        result.push(element("paymentType", v));
     }
     result.push("</DomesticTransaction>".to_string());
 }
@@ -168,12 +178,12 @@ mod tests {
         let mut i = Import::new();
         i.orders.push(Type::Domestic(DomesticTransaction {
             account_from: "1".into(),
-            currency: "CZK".into(),
+            currency: Currency::Czk,
             amount: Decimal::new(500, 0),
             account_to: "2".into(),
             bank_code: "0800".into(),
-            ks: None,
-            vs: Some("123".into()),
+            ks: Some("308".parse().unwrap()),
+            vs: Some("123".parse().unwrap()),
             ss: None,
             date: "2024-01-01".into(),
             message_for_recipient: None,
@@ -184,6 +194,7 @@ mod tests {
         let xml = i.to_xml();
         assert!(xml.contains("<DomesticTransaction>"));
         assert!(xml.contains("<vs>123</vs>"));
+        assert!(xml.contains("<ks>0308</ks>"));
     }
 
     #[test]
@@ -191,10 +202,10 @@ mod tests {
         let mut i = Import::new();
         i.orders.push(Type::Foreign(ForeignTransaction {
             account_from: "1".into(),
-            currency: "USD".into(),
+            currency: Currency::Usd,
             amount: Decimal::new(200, 0),
             account_to: "2".into(),
-            bic: "BIC".into(),
+            bic: "ALFHPKKAXXX".parse().unwrap(),
             date: "2024-01-01".into(),
             benef_name: "N".into(),
             benef_street: "S".into(),
@@ -205,11 +216,28 @@ mod tests {
             remittance_info3: None,
             remittance_info4: None,
             comment: None,
-            payment_reason: "110".into(),
+            payment_reason: PaymentReason::ExportImportGoods,
             details_of_charges: DetailsOfCharges::Shared,
         }));
         let xml = i.to_xml();
         assert!(xml.contains("<detailsOfCharges>470503</detailsOfCharges>"));
+        assert!(xml.contains("<paymentReason>110</paymentReason>"));
+    }
+
+    #[test]
+    fn xml_escapes_text() {
+        let mut order = crate::fixtures::domestic(100);
+        order.message_for_recipient =
+            Some("</messageForRecipient><amount>1</amount> & 'x' \"y\"".into());
+        let xml = Import {
+            orders: vec![Type::Domestic(order)],
+        }
+        .to_xml();
+        assert_eq!(xml.matches("<amount>").count(), 1);
+        assert!(xml.contains(
+            "<messageForRecipient>&lt;/messageForRecipient&gt;&lt;amount&gt;1&lt;/amount&gt; \
+             &amp; &apos;x&apos; &quot;y&quot;</messageForRecipient>"
+        ));
     }
 
     #[test]
@@ -217,7 +245,7 @@ mod tests {
         let mut i = Import::new();
         i.orders.push(Type::Domestic(DomesticTransaction {
             account_from: "1".into(),
-            currency: "CZK".into(),
+            currency: Currency::Czk,
             amount: Decimal::new(1, 0),
             account_to: "2".into(),
             bank_code: "0800".into(),
@@ -232,10 +260,10 @@ mod tests {
         }));
         i.orders.push(Type::Foreign(ForeignTransaction {
             account_from: "1".into(),
-            currency: "USD".into(),
+            currency: Currency::Usd,
             amount: Decimal::new(1, 0),
             account_to: "2".into(),
-            bic: "B".into(),
+            bic: "ALFHPKKAXXX".parse().unwrap(),
             date: "2024-01-01".into(),
             benef_name: "N".into(),
             benef_street: "S".into(),
@@ -246,7 +274,7 @@ mod tests {
             remittance_info3: None,
             remittance_info4: None,
             comment: None,
-            payment_reason: "110".into(),
+            payment_reason: PaymentReason::ExportImportGoods,
             details_of_charges: DetailsOfCharges::Sender,
         }));
         let xml = i.to_xml();
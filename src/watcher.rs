@@ -0,0 +1,294 @@
+//! Polling watcher emitting new movements as events
+//!
+//! [`Watcher`] calls [`Fio::movements_since_last`] in a loop and broadcasts
+//! every movement it receives as a [`NewMovement`] event. Rate limit hits and
+//! network errors are retried with exponential backoff instead of ending the
+//! loop.
+//!
+//! Downloading moves the server bookmark. When the movements cannot be
+//! delivered (nobody is subscribed, the channel has no room for them, or they
+//! cannot be parsed), the watcher resets the bookmark to where it was, so the
+//! next poll downloads them again.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use fiocz_rs::watcher::Watcher;
+//! use fiocz_rs::Fio;
+//!
+//! # async fn run() -> Result<(), fiocz_rs::error::Error> {
+//! let watcher = Watcher::new(Fio::new("your-api-token"), Duration::from_secs(60));
+//! let mut events = watcher.subscribe();
+//! tokio::select! {
+//!     result = watcher.run() => result?,
+//!     () = async {
+//!         while let Ok(event) = events.recv().await {
+//!             println!("{}: {}", event.account_id, event.movement.amount);
+//!         }
+//!     } => {}
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use chrono::NaiveDate;
+use log::{debug, warn};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+use crate::error::Error;
+use crate::types::account_statement::Info;
+use crate::types::movement::Movement;
+use crate::{Fio, MIN_REQUEST_INTERVAL};
+
+/// Default capacity of the event channel
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Default upper bound for the retry delay
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Event emitted for every movement received by the [`Watcher`]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NewMovement {
+    /// Account the movement belongs to
+    pub account_id: String,
+    /// The movement
+    pub movement: Movement,
+}
+
+/// Long-running poller broadcasting new movements
+pub struct Watcher {
+    fio: Fio,
+    interval: Duration,
+    max_backoff: Duration,
+    sender: broadcast::Sender<NewMovement>,
+}
+
+impl Watcher {
+    /// Create new watcher
+    /// # Arguments
+    /// * `fio` - API client
+    /// * `interval` - Polling interval, raised to [`MIN_REQUEST_INTERVAL`] if shorter
+    #[must_use]
+    pub fn new(fio: Fio, interval: Duration) -> Self {
+        let (sender, _) = broadcast::channel(DEFAULT_CAPACITY);
+        Self {
+            fio,
+            interval: interval.max(MIN_REQUEST_INTERVAL),
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            sender,
+        }
+    }
+
+    /// Set the upper bound for the retry delay after failed polls
+    #[must_use]
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff.max(self.interval);
+        self
+    }
+
+    /// Effective polling interval
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Subscribe to movement events
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<NewMovement> {
+        self.sender.subscribe()
+    }
+
+    /// Poll once and broadcast the received movements
+    ///
+    /// Movements that cannot be delivered are left for the next poll by
+    /// resetting the server bookmark.
+    /// # Returns
+    /// * `usize` - Number of movements broadcast
+    /// # Errors
+    /// * `Error::Limit` - Too many requests
+    /// * `Error::InvalidResponse` - A movement could not be parsed; the
+    ///   bookmark was reset
+    pub async fn poll(&self) -> Result<usize, Error> {
+        let statement = self.fio.movements_since_last().await?;
+        let info = &statement.account_statement.info;
+        let mut movements = match statement.movements() {
+            Ok(movements) => movements,
+            Err(e) => {
+                self.restore_bookmark(info).await?;
+                return Err(e);
+            }
+        };
+        if movements.is_empty() {
+            return Ok(0);
+        }
+        let count = movements.len();
+        let queued = self.sender.len();
+        if self.sender.receiver_count() == 0 {
+            warn!("Nobody is subscribed, leaving {count} movements for the next poll");
+            self.restore_bookmark(info).await?;
+            return Ok(0);
+        }
+        // Receivers lagging behind a full channel would miss the oldest events
+        if queued > 0 && queued + count > DEFAULT_CAPACITY {
+            warn!("{queued} events not received yet, leaving {count} movements for the next poll");
+            self.restore_bookmark(info).await?;
+            return Ok(0);
+        }
+        movements.sort_by_key(|m| m.id);
+        for movement in movements {
+            // Cannot fail, there is at least one receiver
+            let _ = self.sender.send(NewMovement {
+                account_id: info.account_id.clone(),
+                movement,
+            });
+        }
+        debug!("Watcher received {count} movements");
+        Ok(count)
+    }
+
+    /// Move the server bookmark back to where it was before the download
+    async fn restore_bookmark(&self, info: &Info) -> Result<(), Error> {
+        if let Some(id) = info.id_last_download {
+            return self.fio.set_last_id(&id.to_string()).await;
+        }
+        // First download on the account: everything from its first day
+        let start = info.date_start.get(..10).unwrap_or(&info.date_start);
+        let day_before = NaiveDate::parse_from_str(start, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.pred_opt())
+            .ok_or_else(|| Error::InvalidResponse(format!("Invalid dateStart {start}")))?;
+        self.fio.set_last_date(&day_before.to_string()).await
+    }
+
+    /// Poll forever
+    ///
+    /// Retryable failures (rate limit, network and server errors) double the
+    /// delay before the next poll up to the configured maximum; the delay is
+    /// reset after the next successful poll.
+    /// # Errors
+    /// * `Error::InvalidToken` - The token is invalid, polling cannot continue
+    /// * Any other non-retryable error returned by [`Watcher::poll`]
+    pub async fn run(&self) -> Result<(), Error> {
+        let mut delay = self.interval;
+        loop {
+            match self.poll().await {
+                Ok(_) => delay = self.interval,
+                Err(e) if is_retryable(&e) => {
+                    delay = next_delay(delay, self.max_backoff);
+                    warn!("Watcher poll failed: {e}, retrying in {}s", delay.as_secs());
+                }
+                Err(e) => return Err(e),
+            }
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+fn is_retryable(error: &Error) -> bool {
+    matches!(
+        error.root(),
+        Error::Limit | Error::Reqwest(_) | Error::Malformed
+    )
+}
+
+fn next_delay(current: Duration, max: Duration) -> Duration {
+    current.saturating_mul(2).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{last_json, StatementJson};
+    use crate::mock::MockServer;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn run_retries_with_backoff() {
+        let polls = AtomicUsize::new(0);
+        let server = MockServer::start(move |_, _| match polls.fetch_add(1, Ordering::SeqCst) {
+            0 => (409, vec![]),
+            1 => (200, last_json(&[2, 1], Some(0))),
+            _ => (404, vec![]),
+        })
+        .await;
+        let watcher = Watcher::new(server.fio("token"), Duration::from_secs(30));
+        let mut events = watcher.subscribe();
+        let started = tokio::time::Instant::now();
+
+        assert!(matches!(watcher.run().await, Err(Error::InvalidToken)));
+        // 60 s backoff after the rate limit, then the regular 30 s interval
+        assert!(started.elapsed() >= Duration::from_secs(90));
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(events.recv().await.unwrap().movement.id, 1);
+        assert_eq!(events.recv().await.unwrap().movement.id, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn undelivered_movements_restore_bookmark() {
+        let server = MockServer::start(|_, path| {
+            if path.starts_with("/set-last") {
+                (200, vec![])
+            } else if path.contains("broken") {
+                let statement = StatementJson {
+                    movements: vec!["{}".to_string()],
+                    ..StatementJson::default()
+                };
+                (200, statement.json().into_bytes())
+            } else {
+                (200, last_json(&[11], Some(10)))
+            }
+        })
+        .await;
+        let watcher = Watcher::new(server.fio("token"), MIN_REQUEST_INTERVAL);
+        assert_eq!(watcher.poll().await.unwrap(), 0);
+        assert_eq!(
+            server.requests(),
+            [
+                "GET /last/token/transactions.json",
+                "GET /set-last-id/token/10/"
+            ]
+        );
+
+        let watcher = Watcher::new(server.fio("broken"), MIN_REQUEST_INTERVAL);
+        let _events = watcher.subscribe();
+        assert!(matches!(
+            watcher.poll().await,
+            Err(Error::InvalidResponse(_))
+        ));
+        assert_eq!(
+            server.requests()[2..],
+            [
+                "GET /last/broken/transactions.json",
+                "GET /set-last-date/broken/2024-01-14/"
+            ]
+        );
+    }
+
+    #[test]
+    fn interval_never_below_minimum() {
+        let watcher = Watcher::new(Fio::new("token"), Duration::from_secs(1));
+        assert_eq!(watcher.interval(), MIN_REQUEST_INTERVAL);
+        let watcher = Watcher::new(Fio::new("token"), Duration::from_secs(120));
+        assert_eq!(watcher.interval(), Duration::from_secs(120));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let max = Duration::from_secs(100);
+        assert_eq!(
+            next_delay(Duration::from_secs(30), max),
+            Duration::from_secs(60)
+        );
+        assert_eq!(next_delay(Duration::from_secs(60), max), max);
+        assert_eq!(next_delay(max, max), max);
+    }
+
+    #[test]
+    fn retryable_errors() {
+        assert!(is_retryable(&Error::Limit));
+        assert!(is_retryable(&Error::Malformed));
+        assert!(!is_retryable(&Error::InvalidToken));
+        assert!(!is_retryable(&Error::InvalidDateFormat));
+    }
+}